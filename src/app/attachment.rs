@@ -0,0 +1,235 @@
+use std::{
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+use eyre::{bail, eyre, Result, WrapErr};
+use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, ImageFormat, ImageReader};
+use serde::{Deserialize, Serialize};
+use tui_input::Input;
+
+/// Most images a post can have.
+pub const MAX_ATTACHMENTS: usize = 4;
+
+/// Largest image Bluesky accepts, in bytes.
+pub const MAX_IMAGE_SIZE: usize = 1_000_000;
+
+/// Longest side images are scaled down to when they are too large to upload as they are.
+const MAX_DIMENSION: u32 = 2000;
+
+/// Quality of the JPEG images that downscaled images are saved as.
+const JPEG_QUALITY: u8 = 85;
+
+/// An image attached to the post being written.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Attachment {
+    pub path: PathBuf,
+    pub alt: String,
+}
+
+impl Attachment {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            alt: String::new(),
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+}
+
+/// The prompt for the path of an image to attach, followed by one for its alt text.
+#[derive(Clone)]
+pub enum AttachmentPrompt {
+    Path(Input),
+    /// Alt text for the last attachment.
+    Alt(Input),
+}
+
+impl AttachmentPrompt {
+    pub fn input(&self) -> &Input {
+        match self {
+            Self::Path(input) | Self::Alt(input) => input,
+        }
+    }
+
+    pub fn input_mut(&mut self) -> &mut Input {
+        match self {
+            Self::Path(input) | Self::Alt(input) => input,
+        }
+    }
+}
+
+/// An image ready to be uploaded.
+#[derive(Clone)]
+pub struct ImageData {
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Reads the image at `path`, scaling it down and saving it as JPEG if it is larger than
+/// [`MAX_IMAGE_SIZE`] and `downscale` is set.
+pub fn load(path: &Path, downscale: bool) -> Result<ImageData> {
+    let name = path.display();
+    let bytes = std::fs::read(path).wrap_err_with(|| format!("could not read {name}"))?;
+    let reader = ImageReader::new(Cursor::new(&bytes)).with_guessed_format()?;
+    if !matches!(
+        reader.format(),
+        Some(ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP)
+    ) {
+        bail!("{name} is not a JPEG, PNG or WebP image");
+    }
+
+    if bytes.len() <= MAX_IMAGE_SIZE {
+        let (width, height) = reader.into_dimensions()?;
+        return Ok(ImageData {
+            bytes,
+            width,
+            height,
+        });
+    }
+    if !downscale {
+        bail!("{name} is larger than {} kB", MAX_IMAGE_SIZE / 1000);
+    }
+
+    let image = reader.decode()?;
+    let mut dimension = MAX_DIMENSION.min(image.width().max(image.height()));
+    while dimension >= 100 {
+        let resized = image
+            .resize(dimension, dimension, FilterType::Triangle)
+            .to_rgb8();
+        let mut bytes = Vec::new();
+        JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY).encode_image(&resized)?;
+        if bytes.len() <= MAX_IMAGE_SIZE {
+            return Ok(ImageData {
+                bytes,
+                width: resized.width(),
+                height: resized.height(),
+            });
+        }
+        dimension = dimension * 3 / 4;
+    }
+    Err(eyre!("{name} could not be made small enough to upload"))
+}
+
+/// Whether `path` looks like an image that can be attached.
+pub fn is_image_path(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    path.is_file() && matches!(extension.as_deref(), Some("jpg" | "jpeg" | "png" | "webp"))
+}
+
+/// Turns a typed or drag-and-dropped path into a file path.
+///
+/// Terminals paste dropped files quoted, with backslash-escaped spaces or as `file://` URLs.
+pub fn parse_path(input: &str) -> PathBuf {
+    let mut path = input.trim();
+    for quote in ['\'', '"'] {
+        if path.len() >= 2 && path.starts_with(quote) && path.ends_with(quote) {
+            path = &path[1..path.len() - 1];
+        }
+    }
+
+    let path = match path.strip_prefix("file://") {
+        Some(url) => percent_decode(url),
+        None if cfg!(windows) => path.to_string(),
+        None => unescape(path),
+    };
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .unwrap_or_else(|| PathBuf::from(&path)),
+        None => PathBuf::from(path),
+    }
+}
+
+fn unescape(path: &str) -> String {
+    let mut unescaped = String::with_capacity(path.len());
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+fn percent_decode(url: &str) -> String {
+    let mut bytes = Vec::with_capacity(url.len());
+    let mut rest = url.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let decoded = match tail {
+            [high, low, ..] if byte == b'%' => std::str::from_utf8(&[*high, *low])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match decoded {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dropped_paths() {
+        assert_eq!(parse_path(" /tmp/cat.jpg\n"), PathBuf::from("/tmp/cat.jpg"));
+        assert_eq!(
+            parse_path("'/tmp/my cat.jpg'"),
+            PathBuf::from("/tmp/my cat.jpg")
+        );
+        assert_eq!(
+            parse_path("file:///tmp/my%20cat%E3%81%AD.jpg"),
+            PathBuf::from("/tmp/my catね.jpg")
+        );
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn parses_escaped_spaces() {
+        assert_eq!(
+            parse_path(r"/tmp/my\ cat.jpg"),
+            PathBuf::from("/tmp/my cat.jpg")
+        );
+    }
+
+    #[test]
+    fn downscales_large_images() {
+        let path = std::env::temp_dir().join(format!("bsky_tui_{}.png", std::process::id()));
+        // Noise does not compress, so this is well over the size limit as a PNG.
+        let image = image::RgbImage::from_fn(1200, 900, |x, y| {
+            let n = x.wrapping_mul(2_654_435_761) ^ y.wrapping_mul(40_503);
+            image::Rgb([n as u8, (n >> 8) as u8, (n >> 16) as u8])
+        });
+        image.save(&path).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() as usize > MAX_IMAGE_SIZE);
+
+        let loaded = load(&path, true).unwrap();
+        assert!(loaded.bytes.len() <= MAX_IMAGE_SIZE);
+        // The aspect ratio is kept.
+        let ratio = f64::from(loaded.width) / f64::from(loaded.height);
+        assert!((ratio - 4.0 / 3.0).abs() < 0.01);
+        assert!(load(&path, false).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,410 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Longest text a post can have, in graphemes.
+pub const MAX_GRAPHEMES: usize = 300;
+
+/// An edit or cursor movement in the [`Composer`].
+pub enum ComposerRequest {
+    InsertChar(char),
+    DeletePrevChar,
+    DeleteNextChar,
+    GoToPrevChar,
+    GoToNextChar,
+    /// Moves to the same grapheme of the previous line, or the end of it if it is shorter.
+    GoToPrevLine,
+    GoToNextLine,
+    GoToLineStart,
+    GoToLineEnd,
+}
+
+/// The multi-line text of the post being written.
+///
+/// The cursor is a byte offset into the text and always sits on a grapheme boundary.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Composer {
+    text: String,
+    cursor: usize,
+}
+
+/// The composer text soft-wrapped to a width.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Wrapped {
+    pub lines: Vec<String>,
+    /// Column and row of the cursor.
+    pub cursor: (usize, usize),
+}
+
+impl Composer {
+    /// A composer holding `text`, with the cursor at its end.
+    pub fn new(text: String) -> Self {
+        let cursor = text.len();
+        Self { text, cursor }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn graphemes(&self) -> usize {
+        self.text.graphemes(true).count()
+    }
+
+    pub fn handle(&mut self, req: ComposerRequest) {
+        match req {
+            ComposerRequest::InsertChar(c) => {
+                self.text.insert(self.cursor, c);
+                self.cursor += c.len_utf8();
+            }
+            ComposerRequest::DeletePrevChar => {
+                if let Some(start) = self.prev_boundary() {
+                    self.text.replace_range(start..self.cursor, "");
+                    self.cursor = start;
+                }
+            }
+            ComposerRequest::DeleteNextChar => {
+                if let Some(end) = self.next_boundary() {
+                    self.text.replace_range(self.cursor..end, "");
+                }
+            }
+            ComposerRequest::GoToPrevChar => {
+                if let Some(start) = self.prev_boundary() {
+                    self.cursor = start;
+                }
+            }
+            ComposerRequest::GoToNextChar => {
+                if let Some(end) = self.next_boundary() {
+                    self.cursor = end;
+                }
+            }
+            ComposerRequest::GoToPrevLine => {
+                let start = self.line_start();
+                if start == 0 {
+                    return;
+                }
+                let column = self.text[start..self.cursor].graphemes(true).count();
+                let prev_start = self.text[..start - 1].rfind('\n').map_or(0, |i| i + 1);
+                self.cursor = self.offset_at(prev_start, start - 1, column);
+            }
+            ComposerRequest::GoToNextLine => {
+                let end = self.line_end();
+                if end == self.text.len() {
+                    return;
+                }
+                let column = self.text[self.line_start()..self.cursor]
+                    .graphemes(true)
+                    .count();
+                let next_start = end + 1;
+                let next_end = self.text[next_start..]
+                    .find('\n')
+                    .map_or(self.text.len(), |i| next_start + i);
+                self.cursor = self.offset_at(next_start, next_end, column);
+            }
+            ComposerRequest::GoToLineStart => self.cursor = self.line_start(),
+            ComposerRequest::GoToLineEnd => self.cursor = self.line_end(),
+        }
+    }
+
+    /// Soft-wraps the text to `width` columns, breaking lines after spaces where possible.
+    pub fn wrap(&self, width: u16) -> Wrapped {
+        let width = usize::from(width.max(1));
+        let mut lines = Vec::new();
+        let mut cursor = (0, 0);
+        let mut offset = 0;
+        for line in self.text.split('\n') {
+            let rows = wrap_line(line, width);
+            for (i, &(start, end)) in rows.iter().enumerate() {
+                let (start_offset, end_offset) = (offset + start, offset + end);
+                let is_last = i + 1 == rows.len();
+                if self.cursor >= start_offset
+                    && (self.cursor < end_offset || (is_last && self.cursor == end_offset))
+                {
+                    cursor = (self.text[start_offset..self.cursor].width(), lines.len());
+                }
+                lines.push(line[start..end].to_string());
+            }
+            offset += line.len() + 1;
+        }
+
+        // A cursor after a full row goes to the start of the next one.
+        if cursor.0 >= width {
+            lines.insert(cursor.1 + 1, String::new());
+            cursor = (0, cursor.1 + 1);
+        }
+        Wrapped { lines, cursor }
+    }
+
+    fn prev_boundary(&self) -> Option<usize> {
+        self.text[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map(|(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> Option<usize> {
+        self.text[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map(|grapheme| self.cursor + grapheme.len())
+    }
+
+    fn line_start(&self) -> usize {
+        self.text[..self.cursor].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn line_end(&self) -> usize {
+        self.text[self.cursor..]
+            .find('\n')
+            .map_or(self.text.len(), |i| self.cursor + i)
+    }
+
+    /// Offset of the `column`th grapheme of the line from `start` to `end`, or `end` if the
+    /// line is shorter.
+    fn offset_at(&self, start: usize, end: usize, column: usize) -> usize {
+        self.text[start..end]
+            .grapheme_indices(true)
+            .nth(column)
+            .map_or(end, |(i, _)| start + i)
+    }
+}
+
+impl Wrapped {
+    /// Keeps the `height` rows that end with the one the cursor is on, or the first rows if the
+    /// cursor is near the top.
+    pub fn scrolled(mut self, height: usize) -> Self {
+        let top = (self.cursor.1 + 1).saturating_sub(height);
+        self.lines = self.lines.into_iter().skip(top).take(height).collect();
+        self.cursor.1 -= top;
+        self
+    }
+}
+
+/// Splits `text` into posts of at most [`MAX_GRAPHEMES`] graphemes for a thread, breaking after
+/// a sentence where possible, then between words. With `numbered`, each post ends with its
+/// position in the thread, like ` 1/3`.
+pub fn split_thread(text: &str, numbered: bool) -> Vec<String> {
+    let text = text.trim();
+    if text.graphemes(true).count() <= MAX_GRAPHEMES {
+        return vec![text.to_string()];
+    }
+    if !numbered {
+        return split(text, MAX_GRAPHEMES);
+    }
+
+    // The numbers take room from every post, and get longer as the number of posts grows.
+    let mut digits = 1;
+    loop {
+        let posts = split(text, MAX_GRAPHEMES - (2 * digits + 2));
+        if posts.len() < 10usize.pow(digits as u32) {
+            let count = posts.len();
+            return posts
+                .into_iter()
+                .enumerate()
+                .map(|(i, post)| format!("{post} {}/{count}", i + 1))
+                .collect();
+        }
+        digits += 1;
+    }
+}
+
+/// Joins posts made by [`split_thread`] back into one text, for the part of a thread that was
+/// not sent. With `numbered`, their numbers are dropped, as they are numbered again when split.
+pub fn join_thread(posts: &[String], numbered: bool) -> String {
+    posts
+        .iter()
+        .map(|post| {
+            let number = post.rsplit_once(' ').filter(|(_, number)| {
+                numbered
+                    && number.split_once('/').is_some_and(|(i, count)| {
+                        [i, count]
+                            .iter()
+                            .all(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+                    })
+            });
+            number.map_or(post.as_str(), |(text, _)| text)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn split(text: &str, limit: usize) -> Vec<String> {
+    let mut posts = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let end = rest
+            .grapheme_indices(true)
+            .nth(limit)
+            .map_or(rest.len(), |(i, _)| i);
+        if end == rest.len() {
+            posts.push(rest.to_string());
+            break;
+        }
+
+        // A sentence ending in the second half of the post, or else the last space.
+        let cut = rest
+            .split_sentence_bound_indices()
+            .map(|(i, _)| i)
+            .take_while(|&i| i <= end)
+            .last()
+            .filter(|&i| i >= end / 2)
+            .or_else(|| rest[..end].rfind(char::is_whitespace).filter(|&i| i > 0))
+            .unwrap_or(end);
+        posts.push(rest[..cut].trim_end().to_string());
+        rest = rest[cut..].trim_start();
+    }
+    posts
+}
+
+/// Splits a line without newlines into rows at most `width` columns wide, as byte ranges.
+fn wrap_line(line: &str, width: usize) -> Vec<(usize, usize)> {
+    let mut rows = Vec::new();
+    let (mut start, mut row_width) = (0, 0);
+    // Where the row can be broken: just after its last space.
+    let mut break_at = None;
+    for (i, grapheme) in line.grapheme_indices(true) {
+        let grapheme_width = grapheme.width();
+        if row_width + grapheme_width > width && i > start {
+            let end = match break_at {
+                Some(end) if end > start => end,
+                _ => i,
+            };
+            rows.push((start, end));
+            start = end;
+            row_width = line[start..i].width();
+            break_at = None;
+        }
+        row_width += grapheme_width;
+        if grapheme.chars().all(char::is_whitespace) {
+            break_at = Some(i + grapheme.len());
+        }
+    }
+    rows.push((start, line.len()));
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn composer(text: &str) -> Composer {
+        let mut composer = Composer::default();
+        for c in text.chars() {
+            composer.handle(ComposerRequest::InsertChar(c));
+        }
+        composer
+    }
+
+    #[test]
+    fn counts_graphemes() {
+        // A family emoji and a flag are one grapheme each despite being several code points.
+        let composer = composer("hi 👨‍👩‍👧🇯🇵\n");
+        assert_eq!(composer.graphemes(), 6);
+        assert_eq!(
+            self::composer(&"あ".repeat(MAX_GRAPHEMES)).graphemes(),
+            MAX_GRAPHEMES
+        );
+    }
+
+    #[test]
+    fn edits_whole_graphemes() {
+        let mut composer = composer("a🇯🇵b");
+        composer.handle(ComposerRequest::GoToPrevChar);
+        composer.handle(ComposerRequest::DeletePrevChar);
+        assert_eq!(composer.text(), "ab");
+
+        composer.handle(ComposerRequest::GoToLineStart);
+        composer.handle(ComposerRequest::DeleteNextChar);
+        assert_eq!(composer.text(), "b");
+    }
+
+    #[test]
+    fn moves_between_lines() {
+        let mut composer = composer("first line\nabc\nthird");
+        composer.handle(ComposerRequest::GoToLineStart);
+        composer.handle(ComposerRequest::GoToNextChar);
+        composer.handle(ComposerRequest::GoToPrevLine);
+        composer.handle(ComposerRequest::GoToPrevLine);
+        composer.handle(ComposerRequest::InsertChar('|'));
+        assert_eq!(composer.text(), "f|irst line\nabc\nthird");
+
+        // The second line is shorter, so the cursor ends up at its end.
+        composer.handle(ComposerRequest::GoToLineEnd);
+        composer.handle(ComposerRequest::GoToNextLine);
+        composer.handle(ComposerRequest::InsertChar('|'));
+        assert_eq!(composer.text(), "f|irst line\nabc|\nthird");
+    }
+
+    #[test]
+    fn wraps_after_spaces() {
+        let wrapped = composer("the quick brown fox\n\nsupercalifragilistic").wrap(10);
+        assert_eq!(
+            wrapped.lines,
+            [
+                "the quick ",
+                "brown fox",
+                "",
+                "supercalif",
+                "ragilistic",
+                ""
+            ]
+        );
+        // The cursor is after a full row, so it moves to a new one.
+        assert_eq!(wrapped.cursor, (0, 5));
+
+        let mut composer = composer("the quick brown fox");
+        composer.handle(ComposerRequest::GoToLineStart);
+        for _ in 0..12 {
+            composer.handle(ComposerRequest::GoToNextChar);
+        }
+        assert_eq!(composer.wrap(10).cursor, (2, 1));
+        assert_eq!(
+            composer.wrap(10).scrolled(1),
+            Wrapped {
+                lines: vec!["brown fox".into()],
+                cursor: (2, 0)
+            }
+        );
+    }
+
+    #[test]
+    fn splits_threads_between_sentences() {
+        // 51 graphemes, so five sentences fit in a post but six do not.
+        let sentence = "A sentence that goes on for a while and then ends. ";
+        let text = sentence.repeat(10);
+        let posts = split_thread(&text, false);
+        assert_eq!(
+            posts,
+            [sentence.repeat(5).trim_end(), sentence.repeat(5).trim_end()]
+        );
+
+        let posts = split_thread(&text, true);
+        assert_eq!(
+            posts,
+            [
+                format!("{} 1/2", sentence.repeat(5).trim_end()),
+                format!("{} 2/2", sentence.repeat(5).trim_end())
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_threads_between_words() {
+        let text = "word ".repeat(200);
+        let posts = split_thread(&text, true);
+        assert_eq!(posts.len(), 4);
+        assert!(posts.iter().all(|post| {
+            post.graphemes(true).count() <= MAX_GRAPHEMES && !post.contains("wor ")
+        }));
+        // A short text is left as it is.
+        assert_eq!(split_thread(" hi \n", true), ["hi"]);
+    }
+
+    #[test]
+    fn joins_the_rest_of_a_thread() {
+        let text = "word ".repeat(200);
+        let posts = split_thread(&text, true);
+        assert_eq!(join_thread(&posts, true), text.trim_end());
+        assert_eq!(join_thread(&posts[2..], false), posts[2..].join(" "));
+        assert!(join_thread(&posts[2..], false).ends_with(" 4/4"));
+    }
+}
@@ -5,27 +5,112 @@ use eyre::Result;
 use serde::{Deserialize, Serialize};
 use toml;
 
+use super::{
+    credentials::{Credentials, PasswordStore},
+    preview::ImageProtocol,
+};
+
 #[derive(Default, Clone, Deserialize, Serialize)]
 pub struct AppConfig {
-    pub email: String,
+    /// Email, handle or DID used to log in.
+    #[serde(default, alias = "email")]
+    pub identifier: String,
+    #[serde(default)]
     pub password: String,
+    /// PDS or entryway URL, `https://bsky.social` when unset.
+    pub service: Option<String>,
+    /// Where passwords left empty in this file are looked up.
+    #[serde(default)]
+    pub password_store: PasswordStore,
     pub skip_splash: bool,
     pub splash_path: Option<String>,
+    #[serde(default)]
+    pub accounts: Vec<AccountConfig>,
+    /// Show a thumbnail of the image attached to the selected post next to the list.
+    #[serde(default)]
+    pub image_preview: bool,
+    #[serde(default)]
+    pub image_protocol: ImageProtocol,
+    /// Scale down attached images that are too large to upload, `true` when unset.
+    pub downscale_images: Option<bool>,
+    /// Refuse to send posts with images that have no alt text.
+    #[serde(default)]
+    pub require_alt_text: bool,
+}
+
+#[derive(Default, Clone, Deserialize, Serialize)]
+pub struct AccountConfig {
+    #[serde(alias = "email")]
+    pub identifier: String,
+    pub password: String,
+    pub service: Option<String>,
+}
+
+impl AccountConfig {
+    /// Checks that the account can log in. Only the account being logged in is checked, so a
+    /// half-configured account does not stop the others from being used.
+    pub fn check_required_fields(&self) -> Result<()> {
+        if self.identifier.is_empty() {
+            eyre::bail!("identifier (email, handle or DID) is required");
+        }
+        if self.password.is_empty() {
+            eyre::bail!(
+                "password is required for {} (set it in the config file or run `bsky_tui login {}`)",
+                self.identifier,
+                self.identifier
+            );
+        }
+        Ok(())
+    }
 }
 
 impl AppConfig {
     pub fn new(
-        email: String,
+        identifier: String,
         password: String,
         skip_splash: bool,
         splash_path: Option<String>,
     ) -> Self {
         Self {
-            email,
+            identifier,
             password,
+            service: None,
+            password_store: PasswordStore::default(),
             skip_splash,
             splash_path,
+            accounts: Vec::new(),
+            image_preview: false,
+            image_protocol: ImageProtocol::default(),
+            downscale_images: None,
+            require_alt_text: false,
+        }
+    }
+
+    /// Returns the top-level account (if any) followed by the `[[accounts]]` entries.
+    /// Accounts without their own `service` use the top-level one.
+    pub fn accounts(&self) -> Vec<AccountConfig> {
+        let mut accounts = Vec::new();
+        if !self.identifier.is_empty() {
+            accounts.push(AccountConfig {
+                identifier: self.identifier.clone(),
+                password: self.password.clone(),
+                service: None,
+            });
+        }
+        accounts.extend(self.accounts.iter().cloned());
+        for account in accounts.iter_mut() {
+            account.service = account
+                .service
+                .take()
+                .or_else(|| self.service.clone())
+                .filter(|service| !service.is_empty())
+                .map(|service| service.trim_end_matches('/').to_string());
         }
+        accounts
+    }
+
+    pub fn downscale_images(&self) -> bool {
+        self.downscale_images.unwrap_or(true)
     }
 
     pub fn load() -> Result<Self> {
@@ -33,12 +118,32 @@ impl AppConfig {
         Ok(config)
     }
 
-    pub fn check_required_fields(&self) -> Result<()> {
-        if self.email.is_empty() {
-            eyre::bail!("email is required");
+    /// Replaces the credentials of the account at `index` (as numbered by [`Self::accounts`]).
+    pub fn set_credentials(&mut self, index: usize, identifier: String, password: String) {
+        let has_top_level = !self.identifier.is_empty() || self.accounts.is_empty();
+        if has_top_level && index == 0 {
+            self.identifier = identifier;
+            self.password = password;
+        } else if let Some(account) = self.accounts.get_mut(index - usize::from(has_top_level)) {
+            account.identifier = identifier;
+            account.password = password;
         }
-        if self.password.is_empty() {
-            eyre::bail!("password is required");
+    }
+
+    /// Fills in passwords that are not written in config.toml from the password store.
+    pub fn load_passwords(&mut self) -> Result<()> {
+        let mut credentials = Credentials::new(self.password_store);
+        if !self.identifier.is_empty() && self.password.is_empty() {
+            if let Some(password) = credentials.get(&self.identifier)? {
+                self.password = password;
+            }
+        }
+        for account in self.accounts.iter_mut() {
+            if account.password.is_empty() {
+                if let Some(password) = credentials.get(&account.identifier)? {
+                    account.password = password;
+                }
+            }
         }
         Ok(())
     }
@@ -52,23 +157,45 @@ impl AppConfig {
         config_dir.join("bsky_tui/config.toml")
     }
 
+    /// Where the session of `account` is kept, one file per identifier and service.
+    pub fn session_path(account: &AccountConfig) -> std::path::PathBuf {
+        let config_dir = dirs::config_dir().unwrap();
+        config_dir.join(format!("bsky_tui/sessions/{}.json", session_name(account)))
+    }
+
     pub fn generate_config_file() -> Result<()> {
         let path = Self::config_path();
         let prefix = path.parent().unwrap();
         std::fs::create_dir_all(prefix)?;
 
         let content = toml::to_string(&Self::default())?;
-        let mut file = std::fs::File::create(&path)?;
+        let mut file = create_private_file(&path)?;
         file.write_all(content.as_bytes())?;
 
         Ok(())
     }
 
+    /// Whether the config file can be read by users other than its owner.
+    pub fn is_world_readable() -> bool {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::metadata(Self::config_path())
+                .map(|metadata| metadata.permissions().mode() & 0o004 != 0)
+                .unwrap_or(false)
+        }
+        #[cfg(not(unix))]
+        {
+            false
+        }
+    }
+
     fn read_config() -> Result<Config> {
         let path = Self::config_path();
         let config = Config::builder()
             .set_default("skip_splash", false)?
             .set_default("splash_path", None::<String>)?
+            .set_default("service", None::<String>)?
             .add_source(
                 config::File::from(path)
                     .required(true)
@@ -78,3 +205,73 @@ impl AppConfig {
         Ok(config)
     }
 }
+
+/// The file name of the session of `account`, e.g. `bsky.social_alice.bsky.social`.
+///
+/// Identifiers are typed by the user and DIDs contain colons, so anything that is not safe in a
+/// file name on every platform is replaced with `_`.
+fn session_name(account: &AccountConfig) -> String {
+    let service = account.service.as_deref().unwrap_or("https://bsky.social");
+    let host = service.split_once("://").map_or(service, |(_, host)| host);
+    format!("{host}_{}", account.identifier)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '@') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Creates (or truncates) a file that only the current user can read and write.
+pub fn create_private_file(path: &std::path::Path) -> Result<std::fs::File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    Ok(options.open(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(identifier: &str, service: Option<&str>) -> AccountConfig {
+        AccountConfig {
+            identifier: identifier.to_string(),
+            password: String::new(),
+            service: service.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn session_names_are_safe_and_per_service() {
+        assert_eq!(
+            session_name(&account("alice.bsky.social", None)),
+            "bsky.social_alice.bsky.social"
+        );
+        assert_eq!(
+            session_name(&account(
+                "did:plc:alice",
+                Some("https://pds.example.com:2583")
+            )),
+            "pds.example.com_2583_did_plc_alice"
+        );
+        assert_eq!(
+            session_name(&account("../../alice", None)),
+            "bsky.social_.._.._alice"
+        );
+        assert_ne!(
+            session_name(&account("alice@example.com", None)),
+            session_name(&account(
+                "alice@example.com",
+                Some("https://pds.example.com")
+            ))
+        );
+    }
+}
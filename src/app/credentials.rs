@@ -0,0 +1,262 @@
+use std::{collections::HashMap, io::Write};
+
+use argon2::Argon2;
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+
+const KEYRING_SERVICE: &str = "bsky_tui";
+const PASSPHRASE_ENV: &str = "BSKY_TUI_PASSPHRASE";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Where account passwords are kept.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PasswordStore {
+    /// `password` fields in config.toml.
+    #[default]
+    Config,
+    /// The OS keyring (Secret Service, Keychain or Credential Manager), falling back to the
+    /// encrypted file when no keyring is available.
+    Keyring,
+    /// A passphrase-encrypted file next to config.toml.
+    File,
+}
+
+/// Reads and writes passwords in a [`PasswordStore`].
+///
+/// The encrypted file is decrypted at most once, asking for the passphrase (or reading it from
+/// `BSKY_TUI_PASSPHRASE`) the first time it is needed.
+pub struct Credentials {
+    store: PasswordStore,
+    passphrase: Option<String>,
+    file: Option<HashMap<String, String>>,
+}
+
+impl Credentials {
+    pub fn new(store: PasswordStore) -> Self {
+        Self {
+            store,
+            passphrase: None,
+            file: None,
+        }
+    }
+
+    pub fn get(&mut self, identifier: &str) -> Result<Option<String>> {
+        match self.store {
+            PasswordStore::Config => Ok(None),
+            PasswordStore::Keyring => match keyring_get(identifier) {
+                Ok(Some(password)) => Ok(Some(password)),
+                Ok(None) if !Self::file_path().exists() => Ok(None),
+                _ => self.file_get(identifier),
+            },
+            PasswordStore::File => self.file_get(identifier),
+        }
+    }
+
+    /// Stores `password` and returns a description of where it went.
+    pub fn set(&mut self, identifier: &str, password: &str) -> Result<String> {
+        if self.store == PasswordStore::Keyring && keyring_set(identifier, password).is_ok() {
+            return Ok("the system keyring".into());
+        }
+        self.load_file()?;
+        if let Some(file) = self.file.as_mut() {
+            file.insert(identifier.to_string(), password.to_string());
+        }
+        self.save_file()?;
+        Ok(Self::file_path().display().to_string())
+    }
+
+    pub fn file_path() -> std::path::PathBuf {
+        let config_dir = dirs::config_dir().unwrap();
+        config_dir.join("bsky_tui/credentials.enc")
+    }
+
+    fn file_get(&mut self, identifier: &str) -> Result<Option<String>> {
+        if !Self::file_path().exists() {
+            return Ok(None);
+        }
+        self.load_file()?;
+        Ok(self
+            .file
+            .as_ref()
+            .and_then(|file| file.get(identifier).cloned()))
+    }
+
+    fn load_file(&mut self) -> Result<()> {
+        if self.file.is_some() {
+            return Ok(());
+        }
+        let path = Self::file_path();
+        if !path.exists() {
+            self.file = Some(HashMap::new());
+            return Ok(());
+        }
+
+        let data = std::fs::read(&path)?;
+        let passphrase = self.passphrase(false)?;
+        let file = decrypt(&data, &passphrase).map_err(|e| eyre!("{}: {e}", path.display()))?;
+        self.file = Some(file);
+
+        Ok(())
+    }
+
+    fn save_file(&mut self) -> Result<()> {
+        let path = Self::file_path();
+        let passphrase = self.passphrase(!path.exists())?;
+        let data = encrypt(self.file.as_ref().unwrap_or(&HashMap::new()), &passphrase)?;
+
+        if let Some(prefix) = path.parent() {
+            std::fs::create_dir_all(prefix)?;
+        }
+        let mut file = super::config::create_private_file(&path)?;
+        file.write_all(&data)?;
+
+        Ok(())
+    }
+
+    fn passphrase(&mut self, confirm: bool) -> Result<String> {
+        if let Some(passphrase) = &self.passphrase {
+            return Ok(passphrase.clone());
+        }
+        let passphrase = match std::env::var(PASSPHRASE_ENV) {
+            Ok(passphrase) => passphrase,
+            Err(_) => {
+                let passphrase = rpassword::prompt_password("Credentials file passphrase: ")?;
+                if confirm && rpassword::prompt_password("Confirm passphrase: ")? != passphrase {
+                    eyre::bail!("passphrases do not match");
+                }
+                passphrase
+            }
+        };
+        if passphrase.is_empty() {
+            eyre::bail!("passphrase must not be empty");
+        }
+        self.passphrase = Some(passphrase.clone());
+        Ok(passphrase)
+    }
+}
+
+/// Encrypts `passwords` with a key derived from `passphrase`, as the salt, the nonce and then
+/// the ciphertext.
+fn encrypt(passwords: &HashMap<String, String>, passphrase: &str) -> Result<Vec<u8>> {
+    let plaintext = toml::to_string(passwords)?;
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| eyre!("failed to encrypt credentials"))?;
+
+    Ok([&salt[..], &nonce[..], &ciphertext[..]].concat())
+}
+
+/// Decrypts the passwords written by [`encrypt`].
+fn decrypt(data: &[u8], passphrase: &str) -> Result<HashMap<String, String>> {
+    if data.len() < SALT_LEN + NONCE_LEN {
+        eyre::bail!("the file is corrupted");
+    }
+    let (salt, rest) = data.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| eyre!("wrong passphrase, or the file is corrupted"))?;
+    Ok(toml::from_str(&String::from_utf8(plaintext)?)?)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| eyre!("failed to derive key: {e}"))?;
+    Ok(key)
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "macos",
+    target_os = "windows"
+))]
+fn keyring_get(identifier: &str) -> Result<Option<String>> {
+    match keyring::Entry::new(KEYRING_SERVICE, identifier)?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "macos",
+    target_os = "windows"
+))]
+fn keyring_set(identifier: &str, password: &str) -> Result<()> {
+    keyring::Entry::new(KEYRING_SERVICE, identifier)?.set_password(password)?;
+    Ok(())
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "macos",
+    target_os = "windows"
+)))]
+fn keyring_get(_identifier: &str) -> Result<Option<String>> {
+    eyre::bail!("no keyring is available on this platform")
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "freebsd",
+    target_os = "openbsd",
+    target_os = "macos",
+    target_os = "windows"
+)))]
+fn keyring_set(_identifier: &str, _password: &str) -> Result<()> {
+    eyre::bail!("no keyring is available on this platform")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passwords() -> HashMap<String, String> {
+        HashMap::from([
+            ("alice.bsky.social".to_string(), "app-password".to_string()),
+            ("did:plc:bob".to_string(), "p4ss \"word\"".to_string()),
+        ])
+    }
+
+    #[test]
+    fn passwords_round_trip() {
+        let data = encrypt(&passwords(), "correct horse").unwrap();
+        assert_eq!(decrypt(&data, "correct horse").unwrap(), passwords());
+        // A new salt and nonce every time.
+        assert_ne!(encrypt(&passwords(), "correct horse").unwrap(), data);
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected() {
+        let data = encrypt(&passwords(), "correct horse").unwrap();
+        assert!(decrypt(&data, "battery staple").is_err());
+    }
+
+    #[test]
+    fn truncated_file_is_rejected() {
+        let data = encrypt(&passwords(), "correct horse").unwrap();
+        assert!(decrypt(&data[..SALT_LEN + NONCE_LEN - 1], "correct horse").is_err());
+        assert!(decrypt(&data[..data.len() - 1], "correct horse").is_err());
+    }
+}
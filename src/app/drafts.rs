@@ -0,0 +1,124 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::app::{attachment::Attachment, config};
+
+/// What a draft is written as.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum DraftKind {
+    Post,
+    Thread,
+    /// The rest of a thread that stopped part way, continuing from the post at `uri`.
+    ThreadReply {
+        uri: String,
+    },
+    /// A reply to the post at `uri`.
+    Reply {
+        uri: String,
+    },
+    /// A post quoting the post at `uri`.
+    Quote {
+        uri: String,
+    },
+}
+
+/// Text that was being written but not sent.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Draft {
+    #[serde(flatten)]
+    pub kind: DraftKind,
+    pub text: String,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    pub saved_at: DateTime<Local>,
+}
+
+impl Draft {
+    /// Whether there is anything worth keeping.
+    pub fn is_empty(&self) -> bool {
+        self.text.trim().is_empty() && self.attachments.is_empty()
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct DraftsFile {
+    #[serde(default)]
+    drafts: Vec<Draft>,
+}
+
+/// Where the drafts of the account `did` are kept.
+pub fn path(did: &str) -> PathBuf {
+    let data_dir = dirs::data_dir().unwrap_or_else(std::env::temp_dir);
+    // DIDs contain colons, which Windows does not allow in file names.
+    data_dir.join(format!("bsky_tui/drafts/{}.toml", did.replace(':', "_")))
+}
+
+/// Reads the drafts kept at `path`, most recent first.
+pub fn load(path: &Path) -> Result<Vec<Draft>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("could not read {}", path.display()))?;
+    let file: DraftsFile = toml::from_str(&contents)
+        .wrap_err_with(|| format!("could not parse {}", path.display()))?;
+    Ok(file.drafts)
+}
+
+/// Replaces the drafts kept at `path` with `drafts`.
+pub fn save(path: &Path, drafts: &[Draft]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let contents = toml::to_string(&DraftsFile {
+        drafts: drafts.to_vec(),
+    })?;
+    config::create_private_file(path)?
+        .write_all(contents.as_bytes())
+        .wrap_err_with(|| format!("could not write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drafts_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "bsky_tui_drafts_{}/drafts.toml",
+            std::process::id()
+        ));
+        let mut attachment = Attachment::new("/tmp/cat.jpg".into());
+        attachment.alt = "A cat".into();
+        let drafts = vec![
+            Draft {
+                kind: DraftKind::Reply {
+                    uri: "at://did:plc:abc/app.bsky.feed.post/3k".into(),
+                },
+                text: "Line one\nline two".into(),
+                attachments: vec![attachment],
+                saved_at: Local::now(),
+            },
+            Draft {
+                kind: DraftKind::Thread,
+                text: "A long thread".into(),
+                attachments: Vec::new(),
+                saved_at: Local::now(),
+            },
+        ];
+
+        assert!(load(&path).unwrap().is_empty());
+        save(&path, &drafts).unwrap();
+        assert_eq!(load(&path).unwrap(), drafts);
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
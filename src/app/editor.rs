@@ -0,0 +1,102 @@
+use std::io::Write;
+
+use eyre::{bail, eyre, Result, WrapErr};
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set.
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Lines starting with this are left out of the post, like in Git commit messages. A hashtag
+/// at the start of a line has no space after the `#`, so it is kept.
+const COMMENT: &str = "# ";
+
+/// Builds the contents of the file opened in the editor: the text written so far, followed by
+/// `context` as comments.
+pub fn template(text: &str, context: &[String]) -> String {
+    let mut contents = text.to_string();
+    if !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push('\n');
+    for line in context {
+        contents.push_str(COMMENT);
+        contents.push_str(line);
+        contents.push('\n');
+    }
+    contents.push_str(COMMENT);
+    contents.push_str("Lines starting with '# ' are ignored. Save and quit to go back.\n");
+    contents
+}
+
+/// Takes the post text out of the edited file, dropping comments and the blank lines around it.
+pub fn parse(contents: &str) -> String {
+    contents
+        .lines()
+        .filter(|line| !(line.starts_with(COMMENT) || *line == COMMENT.trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_start_matches('\n')
+        .trim_end()
+        .to_string()
+}
+
+/// Opens `$VISUAL` or `$EDITOR` on a temporary file holding `contents` and returns what the
+/// file holds once the editor exits.
+///
+/// The terminal must have been handed over to the editor before calling this.
+pub async fn edit(contents: &str) -> Result<String> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string());
+    // Editors such as `code --wait` come with arguments.
+    let mut words = editor.split_whitespace();
+    let program = words.next().ok_or_else(|| eyre!("no editor set"))?;
+
+    // Created anew under a random name and readable only by us, as it may hold an unsent post.
+    // It is deleted when dropped, whichever way this returns.
+    let mut file = tempfile::Builder::new()
+        .prefix("bsky_tui_post_")
+        .suffix(".txt")
+        .tempfile()
+        .wrap_err("could not create a temporary file")?;
+    file.write_all(contents.as_bytes())
+        .and_then(|()| file.flush())
+        .wrap_err_with(|| format!("could not write {}", file.path().display()))?;
+    let status = tokio::process::Command::new(program)
+        .args(words)
+        .arg(file.path())
+        .status()
+        .await
+        .wrap_err_with(|| format!("could not run {editor}"));
+    // Read by path, as some editors save by replacing the file.
+    let edited = tokio::fs::read_to_string(file.path()).await;
+
+    if !status?.success() {
+        bail!("{editor} exited with an error, the text was left as it was");
+    }
+    Ok(edited?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_are_left_out() {
+        let contents = template(
+            "Hello\n#rust is nice",
+            &["Replying to @alice.bsky.social".into(), "> hi".into()],
+        );
+        assert_eq!(
+            contents,
+            "Hello\n#rust is nice\n\n# Replying to @alice.bsky.social\n# > hi\n\
+             # Lines starting with '# ' are ignored. Save and quit to go back.\n"
+        );
+        assert_eq!(parse(&contents), "Hello\n#rust is nice");
+        assert_eq!(parse("\n#\n  indented\n\n\n"), "  indented");
+    }
+}
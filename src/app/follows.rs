@@ -0,0 +1,200 @@
+use std::fmt;
+
+use atrium_api::app::bsky::actor::defs::ProfileViewData;
+use ratatui::widgets::ListState;
+use tui_input::{Input, InputRequest};
+
+use crate::app::pages::{Page, Pages};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowListKind {
+    /// The accounts following the subject.
+    Followers,
+    /// The accounts the subject follows.
+    Follows,
+    /// The accounts we muted.
+    Mutes,
+    /// The accounts we blocked.
+    Blocks,
+}
+
+impl fmt::Display for FollowListKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let str = match self {
+            FollowListKind::Followers => "Followers",
+            FollowListKind::Follows => "Following",
+            FollowListKind::Mutes => "Muted accounts",
+            FollowListKind::Blocks => "Blocked accounts",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// A page of the followers of an account or of the accounts it follows, or of the accounts we
+/// muted or blocked.
+#[derive(Clone)]
+pub struct FollowList {
+    pub kind: FollowListKind,
+    /// Whose followers or follows are listed, `None` for our mutes and blocks.
+    pub subject: Option<ProfileViewData>,
+    pub accounts: Vec<ProfileViewData>,
+    pub pages: Pages,
+    /// Narrows the loaded accounts to those whose handle or name contains it.
+    pub filter: Input,
+    /// Whether keys go to the filter box.
+    pub is_filtering: bool,
+    pub list_state: ListState,
+    pub list_position: usize,
+}
+
+impl FollowList {
+    pub fn new(
+        kind: FollowListKind,
+        subject: Option<ProfileViewData>,
+        accounts: Vec<ProfileViewData>,
+        cursor: Option<String>,
+    ) -> Self {
+        Self {
+            kind,
+            subject,
+            accounts,
+            pages: Pages::new(cursor),
+            filter: Input::default(),
+            is_filtering: false,
+            list_state: ListState::default().with_selected(Some(0)),
+            list_position: 0,
+        }
+    }
+
+    /// The DID the list is loaded for, empty for our mutes and blocks.
+    pub fn actor(&self) -> String {
+        self.subject
+            .as_ref()
+            .map(|subject| subject.did.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Shows `accounts` as `page`, with `next_cursor` for the page after it.
+    pub fn set_page(
+        &mut self,
+        page: &Page,
+        accounts: Vec<ProfileViewData>,
+        next_cursor: Option<String>,
+    ) {
+        if !self.pages.set_page(page, next_cursor) {
+            self.list_position = 0;
+        }
+        self.accounts = accounts;
+        self.clamp_position();
+    }
+
+    /// The loaded accounts that match the filter.
+    pub fn visible_accounts(&self) -> Vec<&ProfileViewData> {
+        let filter = self.filter.value().trim().to_lowercase();
+        self.accounts
+            .iter()
+            .filter(|account| {
+                filter.is_empty()
+                    || account.handle.as_str().to_lowercase().contains(&filter)
+                    || account
+                        .display_name
+                        .as_ref()
+                        .is_some_and(|name| name.to_lowercase().contains(&filter))
+            })
+            .collect()
+    }
+
+    pub fn current_account(&self) -> Option<ProfileViewData> {
+        self.visible_accounts()
+            .get(self.list_position)
+            .map(|account| (*account).clone())
+    }
+
+    /// Drops the account with `did`, as when it is unmuted or unblocked from the list.
+    pub fn remove_account(&mut self, did: &str) {
+        self.accounts.retain(|account| account.did.as_str() != did);
+        self.clamp_position();
+    }
+
+    pub fn handle_filter_input(&mut self, req: InputRequest) {
+        self.filter.handle(req);
+        self.list_position = 0;
+        self.list_state.select(Some(0));
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.filter.reset();
+        self.is_filtering = false;
+        self.clamp_position();
+    }
+
+    pub fn move_scroll_up(&mut self) {
+        if self.list_position > 0 {
+            self.list_position -= 1;
+            self.list_state.select(Some(self.list_position));
+        }
+    }
+
+    pub fn move_scroll_down(&mut self) {
+        if self.list_position + 1 < self.visible_accounts().len() {
+            self.list_position += 1;
+            self.list_state.select(Some(self.list_position));
+        }
+    }
+
+    fn clamp_position(&mut self) {
+        let len = self.visible_accounts().len();
+        self.list_position = self.list_position.min(len.saturating_sub(1));
+        self.list_state.select(Some(self.list_position));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(handle: &str, display_name: Option<&str>) -> ProfileViewData {
+        serde_json::from_value(serde_json::json!({
+            "did": format!("did:plc:{}", handle.replace('.', "")),
+            "handle": handle,
+            "displayName": display_name,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn filter_narrows_the_loaded_accounts() {
+        let mut list = FollowList::new(
+            FollowListKind::Followers,
+            Some(account("subject.bsky.social", None)),
+            vec![
+                account("alice.bsky.social", Some("Alice")),
+                account("bob.example.com", Some("Bobby Tables")),
+                account("carol.bsky.social", None),
+            ],
+            Some("next".into()),
+        );
+        list.move_scroll_down();
+        list.move_scroll_down();
+        assert_eq!(list.list_position, 2);
+
+        for c in "TAB".chars() {
+            list.handle_filter_input(InputRequest::InsertChar(c));
+        }
+        assert_eq!(
+            list.current_account().unwrap().handle.as_str(),
+            "bob.example.com"
+        );
+        list.move_scroll_down();
+        assert_eq!(list.list_position, 0);
+
+        list.clear_filter();
+        assert_eq!(list.visible_accounts().len(), 3);
+
+        list.move_scroll_down();
+        list.move_scroll_down();
+        list.remove_account("did:plc:carolbskysocial");
+        assert_eq!(list.visible_accounts().len(), 2);
+        assert_eq!(list.list_position, 1);
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::{DateTime, Duration, Local};
+
+/// How long a toast stays on screen unless dismissed earlier.
+const INFO_TTL: Duration = Duration::seconds(3);
+const ERROR_TTL: Duration = Duration::seconds(10);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MessageLevel {
+    Info,
+    Error,
+}
+
+/// A status or error message shown as a toast and kept in the message log.
+#[derive(Clone)]
+pub struct Message {
+    pub level: MessageLevel,
+    pub text: String,
+    pub time: DateTime<Local>,
+}
+
+impl Message {
+    pub fn info(text: impl Into<String>) -> Self {
+        Self::new(MessageLevel::Info, text.into())
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Self::new(MessageLevel::Error, text.into())
+    }
+
+    fn new(level: MessageLevel, text: String) -> Self {
+        Self {
+            level,
+            text,
+            time: Local::now(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.level == MessageLevel::Error
+    }
+
+    pub fn is_expired(&self) -> bool {
+        let ttl = match self.level {
+            MessageLevel::Info => INFO_TTL,
+            MessageLevel::Error => ERROR_TTL,
+        };
+        Local::now() - self.time > ttl
+    }
+}
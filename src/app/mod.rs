@@ -1,57 +1,117 @@
+pub mod attachment;
+pub mod composer;
 pub mod config;
+pub mod credentials;
+pub mod drafts;
+pub mod editor;
+pub mod follows;
+pub mod message;
+pub mod muted_words;
+pub mod pages;
+pub mod preview;
+pub mod profile;
 pub mod state;
+pub mod thread;
 pub mod ui;
 
-use atrium_api::types::string::{Did, Handle};
-use bsky_sdk::BskyAgent;
+use std::{collections::HashMap, path::PathBuf};
+
+use atrium_api::{
+    app::bsky::feed::post,
+    types::string::{Did, Handle},
+};
+use bsky_sdk::{api::types::TryFromUnknown, BskyAgent};
+use eyre::Result;
 use tui_input::{Input, InputRequest};
 
-use self::state::AppState;
+use self::{
+    attachment::{Attachment, AttachmentPrompt, MAX_ATTACHMENTS},
+    composer::{Composer, ComposerRequest, MAX_GRAPHEMES},
+    drafts::Draft,
+    follows::FollowListKind,
+    message::Message,
+    muted_words::MutedWordPrompt,
+    preview::{Thumbnail, ThumbnailCache},
+    state::{AppState, Moderation},
+};
 use crate::{
     app::{config::AppConfig, state::Tab},
+    bsky,
     inputs::key::Key,
-    io::{IoEvent, SearchEvent, TimelineEvent},
+    io::{
+        FollowsEvent, IoEvent, LoginEvent, ProfileEvent, SearchEvent, ThreadEvent, TimelineEvent,
+    },
 };
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum AppReturn {
     Exit,
     Continue,
+    /// Hands the terminal over to an external editor to write the post in.
+    OpenEditor,
 }
 
 pub struct App {
     io_tx: tokio::sync::mpsc::Sender<IoEvent>,
     is_loading: bool,
     pub state: AppState,
+    /// States of the accounts that are logged in but not currently shown, by account index.
+    account_states: HashMap<usize, AppState>,
+    thumbnails: ThumbnailCache,
 }
 
 impl App {
     pub fn new(io_tx: tokio::sync::mpsc::Sender<IoEvent>) -> Self {
         let is_loading = false;
         let state = AppState::default();
+        let account_states = HashMap::new();
+        let thumbnails = ThumbnailCache::default();
 
         Self {
             io_tx,
             is_loading,
             state,
+            account_states,
+            thumbnails,
         }
     }
 
     pub async fn do_action(&mut self, key: Key) -> AppReturn {
+        if self.state.is_login() {
+            return self.login_action(key).await;
+        }
         match self.state.get_mode() {
+            state::Mode::Normal if self.state.is_thread_view() => self.thread_action(key).await,
+            state::Mode::Normal if self.state.is_profile_view() => self.profile_action(key).await,
+            state::Mode::Normal if self.state.is_follow_list_view() => {
+                self.follow_list_action(key).await
+            }
             state::Mode::Normal => match self.state.get_tab() {
                 Tab::Home => self.timeline_action(key).await,
                 Tab::Notifications => self.notifications_action(key).await,
                 Tab::Search => self.search_action(key).await,
             },
-            state::Mode::Post => self.post_action(key).await,
-            state::Mode::Reply => self.reply_action(key).await,
+            state::Mode::Post
+            | state::Mode::NewThread
+            | state::Mode::Reply
+            | state::Mode::Quote => self.composer_action(key).await,
             state::Mode::Help => self.help_action(key).await,
             state::Mode::Search => self.search_input_action(key).await,
+            state::Mode::GoToProfile => self.profile_input_action(key).await,
+            state::Mode::Accounts => self.accounts_action(key).await,
+            state::Mode::Messages => self.messages_action(key).await,
+            state::Mode::Links => self.links_action(key).await,
+            state::Mode::Drafts => self.drafts_action(key).await,
+            state::Mode::DeletePost => self.delete_post_action(key).await,
+            state::Mode::Moderate => self.moderation_action(key).await,
+            state::Mode::MutedWords => self.muted_words_action(key).await,
         }
     }
 
     async fn timeline_action(&mut self, key: Key) -> AppReturn {
+        if let Some(ret) = self.shared_action(key).await {
+            return ret;
+        }
         match key {
             Key::Char('q') | Key::Esc | Key::Ctrl('c') => AppReturn::Exit,
             Key::Char('r') => {
@@ -63,10 +123,29 @@ impl App {
                 self.state.set_mode(state::Mode::Post);
                 AppReturn::Continue
             }
+            Key::Char('T') => {
+                self.state.set_mode(state::Mode::NewThread);
+                AppReturn::Continue
+            }
+            Key::Char('D') => {
+                self.state.move_drafts_scroll_top();
+                self.state.set_mode(state::Mode::Drafts);
+                AppReturn::Continue
+            }
             Key::Char('N') => {
                 self.state.set_mode(state::Mode::Reply);
                 AppReturn::Continue
             }
+            Key::Char('Q') => {
+                if self.state.get_selected_post().is_some() {
+                    self.state.set_mode(state::Mode::Quote);
+                }
+                AppReturn::Continue
+            }
+            Key::Char('d') => {
+                self.confirm_delete_post();
+                AppReturn::Continue
+            }
             Key::Ctrl('r') => {
                 self.dispatch(IoEvent::Repost).await;
                 AppReturn::Continue
@@ -75,10 +154,6 @@ impl App {
                 self.dispatch(IoEvent::Like).await;
                 AppReturn::Continue
             }
-            Key::Char('?') => {
-                self.state.set_mode(state::Mode::Help);
-                AppReturn::Continue
-            }
             Key::Char('/') => {
                 self.state.set_mode(state::Mode::Search);
                 self.state.set_input(Input::default());
@@ -94,7 +169,24 @@ impl App {
             }
             Key::Enter => {
                 if let Some(feed) = self.state.get_current_feed() {
-                    if let Some(id) = feed.post.uri.split('/').last() {
+                    if self.state.reveal_post(&feed.post.uri) {
+                        return AppReturn::Continue;
+                    }
+                    self.dispatch(IoEvent::LoadThread(ThreadEvent::Open(
+                        feed.post.uri.clone(),
+                    )))
+                    .await;
+                }
+                AppReturn::Continue
+            }
+            Key::Char('L') => {
+                self.state.move_links_scroll_top();
+                self.state.set_mode(state::Mode::Links);
+                AppReturn::Continue
+            }
+            Key::Char('o') => {
+                if let Some(feed) = self.state.get_current_feed() {
+                    if let Some(id) = feed.post.uri.split('/').next_back() {
                         let handle = &feed.post.author.handle;
                         let url =
                             format!("https://bsky.app/profile/{}/post/{}", handle.as_str(), id);
@@ -132,16 +224,15 @@ impl App {
     }
 
     async fn notifications_action(&mut self, key: Key) -> AppReturn {
+        if let Some(ret) = self.shared_action(key).await {
+            return ret;
+        }
         match key {
             Key::Char('q') | Key::Esc | Key::Ctrl('c') => AppReturn::Exit,
             Key::Char('r') => {
                 self.dispatch(IoEvent::LoadNotifications).await;
                 AppReturn::Continue
             }
-            Key::Char('?') => {
-                self.state.set_mode(state::Mode::Help);
-                AppReturn::Continue
-            }
             Key::Char('/') => {
                 self.state.set_mode(state::Mode::Search);
                 self.state.set_input(Input::default());
@@ -155,6 +246,12 @@ impl App {
                 self.state.move_notifications_scroll_up();
                 AppReturn::Continue
             }
+            Key::Enter => {
+                if let Some(notification) = self.state.get_current_notification() {
+                    self.state.reveal_post(&notification.uri);
+                }
+                AppReturn::Continue
+            }
             Key::Tab => {
                 self.state.set_next_tab();
                 match self.state.get_tab() {
@@ -174,6 +271,9 @@ impl App {
     }
 
     async fn search_action(&mut self, key: Key) -> AppReturn {
+        if let Some(ret) = self.shared_action(key).await {
+            return ret;
+        }
         match key {
             Key::Char('q') | Key::Esc | Key::Ctrl('c') => AppReturn::Exit,
             Key::Char('r') => {
@@ -185,6 +285,16 @@ impl App {
                 self.state.set_mode(state::Mode::Reply);
                 AppReturn::Continue
             }
+            Key::Char('Q') => {
+                if self.state.get_selected_post().is_some() {
+                    self.state.set_mode(state::Mode::Quote);
+                }
+                AppReturn::Continue
+            }
+            Key::Char('d') => {
+                self.confirm_delete_post();
+                AppReturn::Continue
+            }
             Key::Ctrl('r') => {
                 self.dispatch(IoEvent::SearchRepost).await;
                 AppReturn::Continue
@@ -193,10 +303,6 @@ impl App {
                 self.dispatch(IoEvent::SearchLike).await;
                 AppReturn::Continue
             }
-            Key::Char('?') => {
-                self.state.set_mode(state::Mode::Help);
-                AppReturn::Continue
-            }
             Key::Char('/') => {
                 self.state.set_mode(state::Mode::Search);
                 self.state.set_input(Input::default());
@@ -211,8 +317,23 @@ impl App {
                 AppReturn::Continue
             }
             Key::Enter => {
+                if let Some(post) = self.state.get_current_search_result() {
+                    if self.state.reveal_post(&post.uri) {
+                        return AppReturn::Continue;
+                    }
+                    self.dispatch(IoEvent::LoadThread(ThreadEvent::Open(post.uri.clone())))
+                        .await;
+                }
+                AppReturn::Continue
+            }
+            Key::Char('L') => {
+                self.state.move_links_scroll_top();
+                self.state.set_mode(state::Mode::Links);
+                AppReturn::Continue
+            }
+            Key::Char('o') => {
                 if let Some(feed) = self.state.get_current_search_result() {
-                    if let Some(id) = feed.uri.split('/').last() {
+                    if let Some(id) = feed.uri.split('/').next_back() {
                         let handle = &feed.author.handle;
                         let url =
                             format!("https://bsky.app/profile/{}/post/{}", handle.as_str(), id);
@@ -265,6 +386,271 @@ impl App {
         }
     }
 
+    async fn thread_action(&mut self, key: Key) -> AppReturn {
+        if let Some(ret) = self.shared_action(key).await {
+            return ret;
+        }
+        match key {
+            Key::Ctrl('c') => AppReturn::Exit,
+            Key::Char('q') | Key::Esc | Key::Backspace | Key::Char('h') | Key::Left => {
+                self.state.pop_view();
+                AppReturn::Continue
+            }
+            Key::Char('r') => {
+                self.dispatch(IoEvent::LoadThread(ThreadEvent::Reload))
+                    .await;
+                AppReturn::Continue
+            }
+            Key::Char('N') => {
+                if self.state.get_current_thread_post().is_some() {
+                    self.state.set_mode(state::Mode::Reply);
+                }
+                AppReturn::Continue
+            }
+            Key::Char('Q') => {
+                if self.state.get_selected_post().is_some() {
+                    self.state.set_mode(state::Mode::Quote);
+                }
+                AppReturn::Continue
+            }
+            Key::Char('d') => {
+                self.confirm_delete_post();
+                AppReturn::Continue
+            }
+            Key::Ctrl('r') => {
+                self.dispatch(IoEvent::ViewRepost).await;
+                AppReturn::Continue
+            }
+            Key::Ctrl('l') => {
+                self.dispatch(IoEvent::ViewLike).await;
+                AppReturn::Continue
+            }
+            Key::Down | Key::Char('j') | Key::Ctrl('n') => {
+                self.state.move_thread_scroll_down();
+                AppReturn::Continue
+            }
+            Key::Up | Key::Char('k') | Key::Ctrl('p') => {
+                self.state.move_thread_scroll_up();
+                AppReturn::Continue
+            }
+            Key::Enter => {
+                if let (Some(post), Some(thread)) = (
+                    self.state.get_current_thread_post(),
+                    self.state.get_thread(),
+                ) {
+                    if post.uri != thread.uri {
+                        self.dispatch(IoEvent::LoadThread(ThreadEvent::Open(post.uri.clone())))
+                            .await;
+                    }
+                }
+                AppReturn::Continue
+            }
+            Key::Char('L') => {
+                self.state.move_links_scroll_top();
+                self.state.set_mode(state::Mode::Links);
+                AppReturn::Continue
+            }
+            Key::Char('o') => {
+                if let Some(post) = self.state.get_current_thread_post() {
+                    if let Some(url) = bsky::get_url(post.author.handle.clone(), post.uri.clone()) {
+                        let _ = webbrowser::open(&url).is_ok();
+                    }
+                }
+                AppReturn::Continue
+            }
+            _ => AppReturn::Continue,
+        }
+    }
+
+    async fn profile_action(&mut self, key: Key) -> AppReturn {
+        if let Some(ret) = self.shared_action(key).await {
+            return ret;
+        }
+        match key {
+            Key::Ctrl('c') => AppReturn::Exit,
+            Key::Char('q') | Key::Esc | Key::Backspace => {
+                self.state.pop_view();
+                AppReturn::Continue
+            }
+            Key::Char('r') => {
+                self.dispatch(IoEvent::LoadProfile(ProfileEvent::Reload))
+                    .await;
+                AppReturn::Continue
+            }
+            Key::Char('f') | Key::Char('g') => {
+                if let Some(profile) = self.state.get_profile() {
+                    let kind = if key == Key::Char('f') {
+                        FollowListKind::Followers
+                    } else {
+                        FollowListKind::Follows
+                    };
+                    self.dispatch(IoEvent::LoadFollows(FollowsEvent::Open(
+                        kind,
+                        profile.actor(),
+                    )))
+                    .await;
+                }
+                AppReturn::Continue
+            }
+            Key::Char('h') | Key::Left => {
+                self.dispatch(IoEvent::LoadProfile(ProfileEvent::Prev))
+                    .await;
+                AppReturn::Continue
+            }
+            Key::Char('l') | Key::Right => {
+                self.dispatch(IoEvent::LoadProfile(ProfileEvent::Next))
+                    .await;
+                AppReturn::Continue
+            }
+            Key::Char('N') => {
+                if self.state.get_selected_post().is_some() {
+                    self.state.set_mode(state::Mode::Reply);
+                }
+                AppReturn::Continue
+            }
+            Key::Char('Q') => {
+                if self.state.get_selected_post().is_some() {
+                    self.state.set_mode(state::Mode::Quote);
+                }
+                AppReturn::Continue
+            }
+            Key::Char('d') => {
+                self.confirm_delete_post();
+                AppReturn::Continue
+            }
+            Key::Ctrl('r') => {
+                self.dispatch(IoEvent::ViewRepost).await;
+                AppReturn::Continue
+            }
+            Key::Ctrl('l') => {
+                self.dispatch(IoEvent::ViewLike).await;
+                AppReturn::Continue
+            }
+            Key::Down | Key::Char('j') | Key::Ctrl('n') => {
+                self.state.move_profile_scroll_down();
+                AppReturn::Continue
+            }
+            Key::Up | Key::Char('k') | Key::Ctrl('p') => {
+                self.state.move_profile_scroll_up();
+                AppReturn::Continue
+            }
+            Key::Enter => {
+                if let Some(post) = self.state.get_selected_post() {
+                    self.dispatch(IoEvent::LoadThread(ThreadEvent::Open(post.uri.clone())))
+                        .await;
+                }
+                AppReturn::Continue
+            }
+            Key::Char('L') => {
+                self.state.move_links_scroll_top();
+                self.state.set_mode(state::Mode::Links);
+                AppReturn::Continue
+            }
+            Key::Char('o') => {
+                if let Some(post) = self.state.get_selected_post() {
+                    if let Some(url) = bsky::get_url(post.author.handle.clone(), post.uri.clone()) {
+                        let _ = webbrowser::open(&url).is_ok();
+                    }
+                }
+                AppReturn::Continue
+            }
+            _ => AppReturn::Continue,
+        }
+    }
+
+    async fn follow_list_action(&mut self, key: Key) -> AppReturn {
+        let Some(mut list) = self.state.get_follow_list() else {
+            return AppReturn::Continue;
+        };
+        if list.is_filtering {
+            match key {
+                Key::Esc => list.clear_filter(),
+                Key::Enter => list.is_filtering = false,
+                Key::Down | Key::Ctrl('n') => list.move_scroll_down(),
+                Key::Up | Key::Ctrl('p') => list.move_scroll_up(),
+                Key::Left | Key::Ctrl('b') => list.handle_filter_input(InputRequest::GoToPrevChar),
+                Key::Right | Key::Ctrl('f') => list.handle_filter_input(InputRequest::GoToNextChar),
+                Key::Ctrl('a') => list.handle_filter_input(InputRequest::GoToStart),
+                Key::Ctrl('e') => list.handle_filter_input(InputRequest::GoToEnd),
+                Key::Backspace | Key::Ctrl('h') => {
+                    list.handle_filter_input(InputRequest::DeletePrevChar)
+                }
+                Key::Char(c) => list.handle_filter_input(InputRequest::InsertChar(c)),
+                _ => {}
+            }
+            self.state.set_follow_list(list);
+            return AppReturn::Continue;
+        }
+        if let Some(ret) = self.shared_action(key).await {
+            return ret;
+        }
+
+        match key {
+            Key::Ctrl('c') => AppReturn::Exit,
+            Key::Esc if !list.filter.value().is_empty() => {
+                list.clear_filter();
+                self.state.set_follow_list(list);
+                AppReturn::Continue
+            }
+            Key::Char('q') | Key::Esc | Key::Backspace => {
+                self.state.pop_view();
+                AppReturn::Continue
+            }
+            Key::Char('/') => {
+                list.is_filtering = true;
+                self.state.set_follow_list(list);
+                AppReturn::Continue
+            }
+            Key::Char('r') => {
+                self.dispatch(IoEvent::LoadFollows(FollowsEvent::Reload))
+                    .await;
+                AppReturn::Continue
+            }
+            Key::Char('h') | Key::Left => {
+                self.dispatch(IoEvent::LoadFollows(FollowsEvent::Prev))
+                    .await;
+                AppReturn::Continue
+            }
+            Key::Char('l') | Key::Right => {
+                self.dispatch(IoEvent::LoadFollows(FollowsEvent::Next))
+                    .await;
+                AppReturn::Continue
+            }
+            Key::Down | Key::Char('j') | Key::Ctrl('n') => {
+                list.move_scroll_down();
+                self.state.set_follow_list(list);
+                AppReturn::Continue
+            }
+            Key::Up | Key::Char('k') | Key::Ctrl('p') => {
+                list.move_scroll_up();
+                self.state.set_follow_list(list);
+                AppReturn::Continue
+            }
+            Key::Enter => {
+                self.open_selected_profile().await;
+                AppReturn::Continue
+            }
+            _ => AppReturn::Continue,
+        }
+    }
+
+    async fn profile_input_action(&mut self, key: Key) -> AppReturn {
+        match key {
+            Key::Enter => {
+                let handle = self.state.get_input().value().trim().to_string();
+                if !handle.is_empty() {
+                    self.state.set_mode(state::Mode::Normal);
+                    self.state.set_input(Input::default());
+                    self.dispatch(IoEvent::LoadProfile(ProfileEvent::Open(handle)))
+                        .await;
+                }
+                AppReturn::Continue
+            }
+            // Editing works as in the search prompt.
+            _ => self.search_input_action(key).await,
+        }
+    }
+
     async fn search_input_action(&mut self, key: Key) -> AppReturn {
         match key {
             Key::Esc => {
@@ -312,82 +698,806 @@ impl App {
         }
     }
 
-    async fn post_action(&mut self, key: Key) -> AppReturn {
+    /// Handles keys while a post, thread, reply or quote is being written.
+    async fn composer_action(&mut self, key: Key) -> AppReturn {
+        if self.state.get_attachment_prompt().is_some() {
+            return self.attachment_prompt_action(key).await;
+        }
         match key {
             Key::Esc => {
+                if self.save_unsent_draft() {
+                    self.state
+                        .push_message(Message::info("Saved as a draft (D lists the drafts)"));
+                }
                 self.state.set_mode(state::Mode::Normal);
-                self.state.set_input(Input::default());
+                self.state.clear_composer();
                 AppReturn::Continue
             }
-            Key::Enter => {
-                self.dispatch(IoEvent::SendPost).await;
+            Key::Enter | Key::CtrlEnter | Key::Ctrl('s') if self.state.is_new_thread_mode() => {
+                if self.check_not_empty() && self.check_alt_text() {
+                    let text = self.state.get_composer().text().to_string();
+                    let numbered = self.state.get_number_thread_posts();
+                    self.dispatch(IoEvent::SendThread(text, numbered)).await;
+                }
+                AppReturn::Continue
+            }
+            Key::Enter | Key::CtrlEnter | Key::Ctrl('s') => {
+                if self.check_not_empty() && self.check_length() && self.check_alt_text() {
+                    // The text goes with the event, so edits made before it is handled are not
+                    // sent.
+                    let text = self.state.get_composer().text().to_string();
+                    let event = match self.state.get_mode() {
+                        state::Mode::Quote => IoEvent::Quote(text),
+                        state::Mode::Reply if self.state.is_view_open() => IoEvent::ViewReply(text),
+                        state::Mode::Reply if self.state.get_current_search_result().is_some() => {
+                            IoEvent::SearchReply(text)
+                        }
+                        state::Mode::Reply => IoEvent::Reply(text),
+                        _ => IoEvent::SendPost(text),
+                    };
+                    self.dispatch(event).await;
+                }
+                AppReturn::Continue
+            }
+            Key::Ctrl('k') if self.state.is_new_thread_mode() => {
+                self.state.toggle_number_thread_posts();
+                AppReturn::Continue
+            }
+            Key::Ctrl('o') => {
+                self.open_attachment_prompt();
+                AppReturn::Continue
+            }
+            Key::Ctrl('t') => {
+                self.open_alt_text_prompt();
+                AppReturn::Continue
+            }
+            Key::Ctrl('x') => {
+                self.state.remove_last_attachment();
+                AppReturn::Continue
+            }
+            Key::Ctrl('g') => AppReturn::OpenEditor,
+            Key::ShiftEnter | Key::AltEnter => {
+                self.state
+                    .handle_composer_input(ComposerRequest::InsertChar('\n'));
                 AppReturn::Continue
             }
             Key::Left | Key::Ctrl('b') => {
-                self.state.move_input_cursor_prev();
+                self.state
+                    .handle_composer_input(ComposerRequest::GoToPrevChar);
                 AppReturn::Continue
             }
             Key::Right | Key::Ctrl('f') => {
-                self.state.move_input_cursor_next();
+                self.state
+                    .handle_composer_input(ComposerRequest::GoToNextChar);
                 AppReturn::Continue
             }
-            Key::Ctrl('a') => {
-                self.state.move_input_cursor_start();
+            Key::Up | Key::Ctrl('p') => {
+                self.state
+                    .handle_composer_input(ComposerRequest::GoToPrevLine);
                 AppReturn::Continue
             }
-            Key::Ctrl('e') => {
-                self.state.move_input_cursor_end();
+            Key::Down | Key::Ctrl('n') => {
+                self.state
+                    .handle_composer_input(ComposerRequest::GoToNextLine);
+                AppReturn::Continue
+            }
+            Key::Home | Key::Ctrl('a') => {
+                self.state
+                    .handle_composer_input(ComposerRequest::GoToLineStart);
+                AppReturn::Continue
+            }
+            Key::End | Key::Ctrl('e') => {
+                self.state
+                    .handle_composer_input(ComposerRequest::GoToLineEnd);
                 AppReturn::Continue
             }
             Key::Char(c) => {
-                self.state.insert_input(InputRequest::InsertChar(c));
+                self.state
+                    .handle_composer_input(ComposerRequest::InsertChar(c));
                 AppReturn::Continue
             }
             Key::Backspace | Key::Ctrl('h') => {
-                self.state.remove_input_prev();
+                self.state
+                    .handle_composer_input(ComposerRequest::DeletePrevChar);
+                AppReturn::Continue
+            }
+            Key::Delete | Key::Ctrl('d') => {
+                self.state
+                    .handle_composer_input(ComposerRequest::DeleteNextChar);
                 AppReturn::Continue
             }
             _ => AppReturn::Continue,
         }
     }
 
-    async fn reply_action(&mut self, key: Key) -> AppReturn {
+    /// Handles keys while the path or alt text of an image is being entered in the composer.
+    async fn attachment_prompt_action(&mut self, key: Key) -> AppReturn {
+        let Some(prompt) = self.state.get_attachment_prompt() else {
+            return AppReturn::Continue;
+        };
         match key {
             Key::Esc => {
-                self.state.set_mode(state::Mode::Normal);
+                self.state.set_attachment_prompt(None);
+            }
+            Key::Enter => match prompt {
+                AttachmentPrompt::Path(input) => {
+                    let path = attachment::parse_path(input.value());
+                    if attachment::is_image_path(&path) {
+                        self.attach(path);
+                    } else {
+                        self.state.push_message(Message::error(format!(
+                            "{} is not a JPEG, PNG or WebP file",
+                            path.display()
+                        )));
+                    }
+                }
+                AttachmentPrompt::Alt(input) => {
+                    self.state
+                        .set_last_attachment_alt(input.value().trim().to_string());
+                    self.state.set_attachment_prompt(None);
+                }
+            },
+            Key::Left | Key::Ctrl('b') => {
+                self.state
+                    .handle_attachment_prompt_input(InputRequest::GoToPrevChar);
+            }
+            Key::Right | Key::Ctrl('f') => {
+                self.state
+                    .handle_attachment_prompt_input(InputRequest::GoToNextChar);
+            }
+            Key::Ctrl('a') => {
+                self.state
+                    .handle_attachment_prompt_input(InputRequest::GoToStart);
+            }
+            Key::Ctrl('e') => {
+                self.state
+                    .handle_attachment_prompt_input(InputRequest::GoToEnd);
+            }
+            Key::Char(c) => {
+                self.state
+                    .handle_attachment_prompt_input(InputRequest::InsertChar(c));
+            }
+            Key::Backspace | Key::Ctrl('h') => {
+                self.state
+                    .handle_attachment_prompt_input(InputRequest::DeletePrevChar);
+            }
+            _ => {}
+        }
+        AppReturn::Continue
+    }
+
+    fn open_attachment_prompt(&mut self) {
+        if self.state.get_attachments().len() >= MAX_ATTACHMENTS {
+            self.state.push_message(Message::error(format!(
+                "A post can have at most {MAX_ATTACHMENTS} images"
+            )));
+        } else {
+            self.state
+                .set_attachment_prompt(Some(AttachmentPrompt::Path(Input::default())));
+        }
+    }
+
+    /// Opens the alt text prompt for the last attached image.
+    fn open_alt_text_prompt(&mut self) {
+        if let Some(attachment) = self.state.get_attachments().pop() {
+            self.state
+                .set_attachment_prompt(Some(AttachmentPrompt::Alt(Input::new(attachment.alt))));
+        }
+    }
+
+    /// Attaches the image at `path` and asks for its alt text.
+    fn attach(&mut self, path: PathBuf) {
+        if self.state.add_attachment(Attachment::new(path)) {
+            self.state
+                .set_attachment_prompt(Some(AttachmentPrompt::Alt(Input::default())));
+        } else {
+            self.state.set_attachment_prompt(None);
+            self.state.push_message(Message::error(format!(
+                "A post can have at most {MAX_ATTACHMENTS} images"
+            )));
+        }
+    }
+
+    /// The file the external editor opens: the text written so far, with the post being replied
+    /// to or quoted in comments.
+    pub fn editor_template(&self) -> String {
+        let target = match self.state.get_mode() {
+            state::Mode::Reply => Some("Replying to"),
+            state::Mode::Quote => Some("Quoting"),
+            _ => None,
+        };
+        let mut context = Vec::new();
+        if let Some((action, post)) = target.zip(self.state.get_selected_post()) {
+            context.push(format!(
+                "{action} {} @{}:",
+                post.author.display_name.clone().unwrap_or_default(),
+                post.author.handle.as_str()
+            ));
+            if let Ok(record) = post::Record::try_from_unknown(post.record) {
+                context.extend(record.text.lines().map(|line| format!("> {line}")));
+            }
+            context.push(String::new());
+        }
+        editor::template(self.state.get_composer().text(), &context)
+    }
+
+    /// Puts the text written in the external editor into the composer.
+    pub fn set_edited_text(&mut self, edited: Result<String>) {
+        match edited {
+            Ok(contents) => self
+                .state
+                .set_composer(Composer::new(editor::parse(&contents))),
+            Err(e) => self
+                .state
+                .push_message(Message::error(format!("Could not edit the post: {e}"))),
+        }
+    }
+
+    /// Keeps `draft` as the most recent draft.
+    pub fn save_draft(&mut self, draft: Draft) {
+        self.state.push_draft(draft);
+        self.store_drafts();
+    }
+
+    /// Removes and returns the draft at `index`.
+    pub fn take_draft(&mut self, index: usize) -> Option<Draft> {
+        let draft = self.state.remove_draft(index)?;
+        self.store_drafts();
+        Some(draft)
+    }
+
+    /// Saves what is being written as a draft, if there is anything. Returns whether there was.
+    pub fn save_unsent_draft(&mut self) -> bool {
+        match self.state.get_draft() {
+            Some(draft) => {
+                self.save_draft(draft);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn store_drafts(&mut self) {
+        let path = drafts::path(self.state.get_did().as_str());
+        if let Err(e) = drafts::save(&path, &self.state.get_drafts()) {
+            self.state
+                .push_message(Message::error(format!("Could not save the drafts: {e:#}")));
+        }
+    }
+
+    /// Handles the keys that do the same in the tabs and in every view: help, accounts,
+    /// messages, and the ones that act on the account the cursor is on or open our account
+    /// lists. Returns `None` for any other key.
+    async fn shared_action(&mut self, key: Key) -> Option<AppReturn> {
+        match key {
+            Key::Char('?') => self.state.set_mode(state::Mode::Help),
+            Key::Char('a') => self.state.set_mode(state::Mode::Accounts),
+            Key::Char('m') => {
+                self.state.move_messages_scroll_top();
+                self.state.set_mode(state::Mode::Messages);
+            }
+            Key::Char('x') => self.state.dismiss_toast(),
+            Key::Char('p') => self.open_selected_profile().await,
+            Key::Char('F') => self.toggle_follow().await,
+            Key::Char('M') => self.confirm_moderation(false),
+            Key::Char('B') => self.confirm_moderation(true),
+            Key::Char('U') | Key::Char('K') => {
+                let kind = if key == Key::Char('U') {
+                    FollowListKind::Mutes
+                } else {
+                    FollowListKind::Blocks
+                };
+                self.dispatch(IoEvent::LoadFollows(FollowsEvent::Open(
+                    kind,
+                    String::new(),
+                )))
+                .await;
+            }
+            Key::Char('W') => {
+                self.state.set_muted_word_prompt(None);
+                self.state.set_mode(state::Mode::MutedWords);
+            }
+            Key::Char('@') => {
+                self.state.set_mode(state::Mode::GoToProfile);
                 self.state.set_input(Input::default());
+            }
+            _ => return None,
+        }
+        Some(AppReturn::Continue)
+    }
+
+    /// Opens the profile of the account the cursor is on: the author of the selected post or
+    /// notification, or the account in a list. Nothing happens if it is already shown.
+    async fn open_selected_profile(&mut self) {
+        let did = if let Some(list) = self.state.get_follow_list() {
+            list.current_account().map(|account| account.did)
+        } else if !self.state.is_view_open() && self.state.get_tab() == Tab::Notifications {
+            self.state
+                .get_current_notification()
+                .map(|notification| notification.author.did.clone())
+        } else {
+            self.state
+                .get_selected_post()
+                .map(|post| post.author.did.clone())
+        };
+        let Some(did) = did.map(|did| did.to_string()) else {
+            return;
+        };
+        if self.state.get_profile().map(|profile| profile.actor()) != Some(did.clone()) {
+            self.dispatch(IoEvent::LoadProfile(ProfileEvent::Open(did)))
+                .await;
+        }
+    }
+
+    /// Follows or unfollows the account of the profile, notification or post the cursor is on.
+    ///
+    /// The change is shown straight away and undone if the request fails. Until the follow
+    /// record is created, the account is shown as followed but cannot be unfollowed.
+    async fn toggle_follow(&mut self) {
+        let Some((did, handle, viewer)) = self.state.get_selected_account() else {
+            return;
+        };
+        if did == self.state.get_did() {
+            return;
+        }
+        if self.state.is_follow_pending(&did) {
+            self.state.push_message(Message::info(format!(
+                "Still following @{}, try again in a moment",
+                handle.as_str()
+            )));
+            return;
+        }
+        match viewer.and_then(|viewer| viewer.following.clone()) {
+            Some(uri) => {
+                self.state.set_following(&did, None);
+                self.dispatch(IoEvent::Unfollow(did, handle, uri)).await;
+            }
+            None => {
+                self.state.set_follow_pending(&did, true);
+                self.dispatch(IoEvent::Follow(did, handle)).await;
+            }
+        }
+    }
+
+    /// Asks whether to mute (or block, with `block`) the account of the profile, notification or
+    /// post the cursor is on, or to undo it if it already is.
+    fn confirm_moderation(&mut self, block: bool) {
+        let Some((did, handle, viewer)) = self.state.get_selected_account() else {
+            return;
+        };
+        if did == self.state.get_did() {
+            self.state
+                .push_message(Message::error("You cannot mute or block yourself"));
+            return;
+        }
+        let moderation = if block {
+            match viewer.and_then(|viewer| viewer.blocking.clone()) {
+                Some(uri) => Moderation::Unblock(did, handle, uri),
+                None => Moderation::Block(did, handle),
+            }
+        } else if viewer.is_some_and(|viewer| viewer.muted == Some(true)) {
+            Moderation::Unmute(did, handle)
+        } else {
+            Moderation::Mute(did, handle)
+        };
+        self.state.set_moderation(Some(moderation));
+        self.state.set_mode(state::Mode::Moderate);
+    }
+
+    /// Asks whether to delete the selected post, if it is one of ours.
+    fn confirm_delete_post(&mut self) {
+        let Some(post) = self.state.get_selected_post() else {
+            return;
+        };
+        if post.author.did != self.state.get_did() {
+            self.state
+                .push_message(Message::error("Only your own posts can be deleted"));
+            return;
+        }
+        self.state.set_post_to_delete(Some(post));
+        self.state.set_mode(state::Mode::DeletePost);
+    }
+
+    /// Returns `false` (and says why) if the text is longer than a post can be.
+    /// Returns `false` (and says why) if nothing but whitespace has been written and no image
+    /// is attached.
+    fn check_not_empty(&mut self) -> bool {
+        let empty = self.state.get_composer().text().trim().is_empty()
+            && self.state.get_attachments().is_empty();
+        if empty {
+            self.state
+                .push_message(Message::error("Write something before sending"));
+        }
+        !empty
+    }
+
+    fn check_length(&mut self) -> bool {
+        let graphemes = self.state.get_composer().graphemes();
+        if graphemes > MAX_GRAPHEMES {
+            self.state.push_message(Message::error(format!(
+                "Posts can be at most {MAX_GRAPHEMES} characters long ({graphemes} written)"
+            )));
+            return false;
+        }
+        true
+    }
+
+    /// Returns `false` (and says why) if alt text is required and an image has none.
+    fn check_alt_text(&mut self) -> bool {
+        let missing = self
+            .state
+            .get_attachments()
+            .iter()
+            .any(|attachment| attachment.alt.is_empty());
+        if missing && self.state.get_config().require_alt_text {
+            self.state.push_message(Message::error(
+                "Add alt text to every image before sending (Ctrl+t edits the last one)",
+            ));
+            return false;
+        }
+        true
+    }
+
+    /// Handles pasted text. Files dropped onto the terminal are pasted as their paths, which
+    /// attach the image while writing a post.
+    pub async fn do_paste(&mut self, pasted: String) -> AppReturn {
+        // The composer keeps line breaks; single-line inputs get spaces instead.
+        let lines: String = pasted
+            .replace("\r\n", "\n")
+            .chars()
+            .map(|c| if c == '\r' { '\n' } else { c })
+            .filter(|&c| c == '\n' || !c.is_control())
+            .collect();
+        let text = lines.replace('\n', " ");
+
+        if self.state.is_login() {
+            for c in text.chars() {
+                self.state.handle_login_input(InputRequest::InsertChar(c));
+            }
+            return AppReturn::Continue;
+        }
+        match self.state.get_mode() {
+            state::Mode::Post
+            | state::Mode::NewThread
+            | state::Mode::Reply
+            | state::Mode::Quote
+                if self.state.get_attachment_prompt().is_some() =>
+            {
+                for c in text.chars() {
+                    self.state
+                        .handle_attachment_prompt_input(InputRequest::InsertChar(c));
+                }
+            }
+            state::Mode::Post
+            | state::Mode::NewThread
+            | state::Mode::Reply
+            | state::Mode::Quote
+                if attachment::is_image_path(&attachment::parse_path(&text)) =>
+            {
+                self.attach(attachment::parse_path(&text));
+            }
+            state::Mode::Post
+            | state::Mode::NewThread
+            | state::Mode::Reply
+            | state::Mode::Quote => {
+                for c in lines.chars() {
+                    self.state
+                        .handle_composer_input(ComposerRequest::InsertChar(c));
+                }
+            }
+            state::Mode::Search | state::Mode::GoToProfile => {
+                for c in text.chars() {
+                    self.state.insert_input(InputRequest::InsertChar(c));
+                }
+            }
+            state::Mode::MutedWords => {
+                if let Some(mut prompt) = self.state.get_muted_word_prompt() {
+                    for c in text.chars() {
+                        prompt.input.handle(InputRequest::InsertChar(c));
+                    }
+                    self.state.set_muted_word_prompt(Some(prompt));
+                }
+            }
+            state::Mode::Normal => {
+                if let Some(mut list) = self
+                    .state
+                    .get_follow_list()
+                    .filter(|list| list.is_filtering)
+                {
+                    for c in text.chars() {
+                        list.handle_filter_input(InputRequest::InsertChar(c));
+                    }
+                    self.state.set_follow_list(list);
+                }
+            }
+            _ => {}
+        }
+        AppReturn::Continue
+    }
+
+    async fn accounts_action(&mut self, key: Key) -> AppReturn {
+        match key {
+            Key::Char('q') | Key::Esc | Key::Char('a') => {
+                self.state.set_mode(state::Mode::Normal);
+                AppReturn::Continue
+            }
+            Key::Down | Key::Char('j') | Key::Ctrl('n') => {
+                self.state.move_accounts_scroll_down();
+                AppReturn::Continue
+            }
+            Key::Up | Key::Char('k') | Key::Ctrl('p') => {
+                self.state.move_accounts_scroll_up();
                 AppReturn::Continue
             }
             Key::Enter => {
-                if let Some(_) = self.state.get_current_search_result() {
-                    self.dispatch(IoEvent::SearchReply).await;
-                } else {
-                    self.dispatch(IoEvent::Reply).await;
+                let index = self.state.get_accounts_list_position();
+                self.state.set_mode(state::Mode::Normal);
+                if index != self.state.get_account_index() {
+                    self.dispatch(IoEvent::SwitchAccount(index)).await;
+                }
+                AppReturn::Continue
+            }
+            _ => AppReturn::Continue,
+        }
+    }
+
+    async fn login_action(&mut self, key: Key) -> AppReturn {
+        if self.state.is_loading() {
+            return match key {
+                Key::Ctrl('c') => AppReturn::Exit,
+                _ => AppReturn::Continue,
+            };
+        }
+        match key {
+            Key::Ctrl('c') => AppReturn::Exit,
+            Key::Esc => match self.state.get_previous_account_index() {
+                Some(index) if self.account_states.contains_key(&index) => {
+                    self.dispatch(IoEvent::SwitchAccount(index)).await;
+                    AppReturn::Continue
+                }
+                _ => AppReturn::Exit,
+            },
+            Key::Tab | Key::Down => {
+                self.state.move_login_focus_next();
+                AppReturn::Continue
+            }
+            Key::Up => {
+                self.state.move_login_focus_prev();
+                AppReturn::Continue
+            }
+            Key::Enter => {
+                let value = |field| {
+                    self.state
+                        .get_login_input(field)
+                        .map(|input| input.value().trim().to_string())
+                };
+                let event = LoginEvent {
+                    account_index: self.state.get_login_account_index(),
+                    identifier: value(state::LoginField::Identifier).unwrap_or_default(),
+                    password: value(state::LoginField::Password).unwrap_or_default(),
+                    auth_factor_token: value(state::LoginField::AuthFactorToken)
+                        .filter(|token| !token.is_empty()),
+                };
+                if !event.identifier.is_empty() && !event.password.is_empty() {
+                    self.dispatch(IoEvent::Login(event)).await;
                 }
                 AppReturn::Continue
             }
             Key::Left | Key::Ctrl('b') => {
-                self.state.move_input_cursor_prev();
+                self.state.handle_login_input(InputRequest::GoToPrevChar);
                 AppReturn::Continue
             }
             Key::Right | Key::Ctrl('f') => {
-                self.state.move_input_cursor_next();
+                self.state.handle_login_input(InputRequest::GoToNextChar);
                 AppReturn::Continue
             }
             Key::Ctrl('a') => {
-                self.state.move_input_cursor_start();
+                self.state.handle_login_input(InputRequest::GoToStart);
                 AppReturn::Continue
             }
             Key::Ctrl('e') => {
-                self.state.move_input_cursor_end();
+                self.state.handle_login_input(InputRequest::GoToEnd);
+                AppReturn::Continue
+            }
+            Key::Ctrl('u') => {
+                self.state.handle_login_input(InputRequest::DeleteLine);
                 AppReturn::Continue
             }
             Key::Char(c) => {
-                self.state.insert_input(InputRequest::InsertChar(c));
+                self.state.handle_login_input(InputRequest::InsertChar(c));
                 AppReturn::Continue
             }
             Key::Backspace | Key::Ctrl('h') => {
-                self.state.remove_input_prev();
+                self.state.handle_login_input(InputRequest::DeletePrevChar);
+                AppReturn::Continue
+            }
+            _ => AppReturn::Continue,
+        }
+    }
+
+    async fn messages_action(&mut self, key: Key) -> AppReturn {
+        match key {
+            Key::Char('q') | Key::Esc | Key::Char('m') => {
+                self.state.set_mode(state::Mode::Normal);
+                AppReturn::Continue
+            }
+            Key::Down | Key::Char('j') | Key::Ctrl('n') => {
+                self.state.move_messages_scroll_down();
+                AppReturn::Continue
+            }
+            Key::Up | Key::Char('k') | Key::Ctrl('p') => {
+                self.state.move_messages_scroll_up();
+                AppReturn::Continue
+            }
+            _ => AppReturn::Continue,
+        }
+    }
+
+    async fn links_action(&mut self, key: Key) -> AppReturn {
+        match key {
+            Key::Char('q') | Key::Esc | Key::Char('L') => {
+                self.state.set_mode(state::Mode::Normal);
+                AppReturn::Continue
+            }
+            Key::Down | Key::Char('j') | Key::Ctrl('n') => {
+                self.state.move_links_scroll_down();
+                AppReturn::Continue
+            }
+            Key::Up | Key::Char('k') | Key::Ctrl('p') => {
+                self.state.move_links_scroll_up();
+                AppReturn::Continue
+            }
+            Key::Enter => {
+                let links = self.state.get_selected_post_links();
+                if let Some(link) = links.get(self.state.get_links_list_position()) {
+                    let _ = webbrowser::open(&link.url).is_ok();
+                }
+                AppReturn::Continue
+            }
+            _ => AppReturn::Continue,
+        }
+    }
+
+    async fn drafts_action(&mut self, key: Key) -> AppReturn {
+        let index = self.state.get_drafts_list_position();
+        match key {
+            Key::Char('q') | Key::Esc | Key::Char('D') => {
+                self.state.set_mode(state::Mode::Normal);
+                AppReturn::Continue
+            }
+            Key::Down | Key::Char('j') | Key::Ctrl('n') => {
+                self.state.move_drafts_scroll_down();
+                AppReturn::Continue
+            }
+            Key::Up | Key::Char('k') | Key::Ctrl('p') => {
+                self.state.move_drafts_scroll_up();
+                AppReturn::Continue
+            }
+            Key::Enter if index < self.state.get_drafts().len() => {
+                self.state.set_mode(state::Mode::Normal);
+                self.dispatch(IoEvent::OpenDraft(index)).await;
+                AppReturn::Continue
+            }
+            Key::Char('s') if index < self.state.get_drafts().len() => {
+                self.state.set_mode(state::Mode::Normal);
+                self.dispatch(IoEvent::SendDraft(index)).await;
+                AppReturn::Continue
+            }
+            Key::Char('d') => {
+                if self.take_draft(index).is_some() {
+                    self.state.push_message(Message::info("Draft deleted"));
+                }
+                AppReturn::Continue
+            }
+            _ => AppReturn::Continue,
+        }
+    }
+
+    async fn delete_post_action(&mut self, key: Key) -> AppReturn {
+        match key {
+            Key::Char('y') | Key::Enter => {
+                self.state.set_mode(state::Mode::Normal);
+                self.dispatch(IoEvent::DeletePost).await;
+                AppReturn::Continue
+            }
+            Key::Char('n') | Key::Char('q') | Key::Esc => {
+                self.state.set_post_to_delete(None);
+                self.state.set_mode(state::Mode::Normal);
+                AppReturn::Continue
+            }
+            _ => AppReturn::Continue,
+        }
+    }
+
+    async fn moderation_action(&mut self, key: Key) -> AppReturn {
+        match key {
+            Key::Char('y') | Key::Enter => {
+                self.state.set_mode(state::Mode::Normal);
+                if let Some(moderation) = self.state.get_moderation() {
+                    self.state.set_moderation(None);
+                    self.dispatch(IoEvent::Moderate(moderation)).await;
+                }
+                AppReturn::Continue
+            }
+            Key::Char('n') | Key::Char('q') | Key::Esc => {
+                self.state.set_moderation(None);
+                self.state.set_mode(state::Mode::Normal);
+                AppReturn::Continue
+            }
+            _ => AppReturn::Continue,
+        }
+    }
+
+    async fn muted_words_action(&mut self, key: Key) -> AppReturn {
+        if let Some(mut prompt) = self.state.get_muted_word_prompt() {
+            match key {
+                Key::Esc => {
+                    self.state.set_muted_word_prompt(None);
+                    return AppReturn::Continue;
+                }
+                Key::Enter => {
+                    let Some(word) = prompt.muted_word() else {
+                        return AppReturn::Continue;
+                    };
+                    // Muting a word again replaces it, to change its target or expiry.
+                    let mut muted_words = self.state.get_muted_words();
+                    muted_words.retain(|muted| !muted.value.eq_ignore_ascii_case(&word.value));
+                    muted_words.push(word);
+                    self.state.set_muted_word_prompt(None);
+                    self.dispatch(IoEvent::SaveMutedWords(muted_words)).await;
+                    return AppReturn::Continue;
+                }
+                Key::Tab => prompt.tags_only = !prompt.tags_only,
+                Key::Ctrl('t') => prompt.duration = prompt.duration.next(),
+                Key::Left | Key::Ctrl('b') => {
+                    prompt.input.handle(InputRequest::GoToPrevChar);
+                }
+                Key::Right | Key::Ctrl('f') => {
+                    prompt.input.handle(InputRequest::GoToNextChar);
+                }
+                Key::Ctrl('a') => {
+                    prompt.input.handle(InputRequest::GoToStart);
+                }
+                Key::Ctrl('e') => {
+                    prompt.input.handle(InputRequest::GoToEnd);
+                }
+                Key::Backspace | Key::Ctrl('h') => {
+                    prompt.input.handle(InputRequest::DeletePrevChar);
+                }
+                Key::Char(c) => {
+                    prompt.input.handle(InputRequest::InsertChar(c));
+                }
+                _ => {}
+            }
+            self.state.set_muted_word_prompt(Some(prompt));
+            return AppReturn::Continue;
+        }
+
+        match key {
+            Key::Char('q') | Key::Esc | Key::Char('W') => {
+                self.state.set_mode(state::Mode::Normal);
+                AppReturn::Continue
+            }
+            Key::Down | Key::Char('j') | Key::Ctrl('n') => {
+                self.state.move_muted_words_scroll_down();
+                AppReturn::Continue
+            }
+            Key::Up | Key::Char('k') | Key::Ctrl('p') => {
+                self.state.move_muted_words_scroll_up();
+                AppReturn::Continue
+            }
+            Key::Char('a') => {
+                self.state
+                    .set_muted_word_prompt(Some(MutedWordPrompt::default()));
+                AppReturn::Continue
+            }
+            Key::Char('d') => {
+                let mut muted_words = self.state.get_muted_words();
+                let index = self.state.get_muted_words_list_position();
+                if index < muted_words.len() {
+                    muted_words.remove(index);
+                    self.dispatch(IoEvent::SaveMutedWords(muted_words)).await;
+                }
                 AppReturn::Continue
             }
             _ => AppReturn::Continue,
@@ -405,9 +1515,30 @@ impl App {
     }
 
     pub async fn update_on_tick(&mut self) -> AppReturn {
+        self.state.expire_toast();
+        self.load_preview_image().await;
         AppReturn::Continue
     }
 
+    /// Starts downloading the thumbnail for the preview pane unless it is already cached.
+    async fn load_preview_image(&mut self) {
+        if let Some(image) = self.state.get_preview_image() {
+            if self.thumbnails.get(&image.url).is_none() {
+                self.thumbnails
+                    .insert(image.url.clone(), Thumbnail::Loading);
+                self.dispatch(IoEvent::LoadThumbnail(image.url)).await;
+            }
+        }
+    }
+
+    pub fn get_thumbnail(&self, url: &str) -> Option<&Thumbnail> {
+        self.thumbnails.get(url)
+    }
+
+    pub fn set_thumbnail(&mut self, url: String, thumbnail: Thumbnail) {
+        self.thumbnails.insert(url, thumbnail);
+    }
+
     pub async fn dispatch(&mut self, action: IoEvent) {
         self.is_loading = true;
         if self.io_tx.send(action).await.is_err() {
@@ -423,8 +1554,59 @@ impl App {
         self.is_loading
     }
 
-    pub fn initialized(&mut self, agent: BskyAgent, handle: Handle, did: Did, config: AppConfig) {
-        self.state = AppState::initialized(agent, handle, did, config);
+    pub fn initialized(
+        &mut self,
+        agent: BskyAgent,
+        handle: Handle,
+        did: Did,
+        config: AppConfig,
+        account_index: usize,
+    ) {
+        let path = drafts::path(did.as_str());
+        let mut state = AppState::initialized(agent, handle, did, config, account_index);
+        match drafts::load(&path) {
+            Ok(drafts) => state.set_drafts(drafts),
+            Err(e) => {
+                state.push_message(Message::error(format!("Could not read the drafts: {e:#}")))
+            }
+        }
+        let previous = std::mem::replace(&mut self.state, state);
+        self.stash_state(previous);
+    }
+
+    /// Shows the login screen, keeping the current account (if any) to return to.
+    pub fn show_login(&mut self, state: AppState) {
+        let previous = std::mem::replace(&mut self.state, state);
+        self.stash_state(previous);
+    }
+
+    /// Brings back the state of an account that was logged in earlier.
+    /// Returns `false` if the account has not been logged in yet.
+    ///
+    /// This is only done from [`IoEvent::SwitchAccount`], so that whatever was still loading for
+    /// the current account has finished, and gone into its state, before it is put away.
+    pub fn restore_account(&mut self, account_index: usize) -> bool {
+        match self.account_states.remove(&account_index) {
+            Some(state) => {
+                let previous = std::mem::replace(&mut self.state, state);
+                self.stash_state(previous);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn stash_state(&mut self, mut state: AppState) {
+        if state.is_initialized() {
+            state.set_mode(state::Mode::Normal);
+            state.set_loading(false);
+            self.account_states.insert(state.get_account_index(), state);
+        }
+    }
+
+    /// Returns the current state followed by the states of the other logged in accounts.
+    pub fn states(&self) -> impl Iterator<Item = &AppState> {
+        std::iter::once(&self.state).chain(self.account_states.values())
     }
 
     pub fn loaded(&mut self) {
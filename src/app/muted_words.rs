@@ -0,0 +1,254 @@
+use std::{collections::HashMap, fmt};
+
+use atrium_api::{
+    app::bsky::{
+        actor::defs::{MutedWord, MutedWordData},
+        feed::{defs::PostViewData, post},
+        notification::list_notifications::Notification,
+    },
+    types::string::{Datetime, Did},
+};
+use bsky_sdk::{api::types::TryFromUnknown, moderation::mutewords::has_muted_word};
+use chrono::Utc;
+use tui_input::Input;
+
+/// Matches the word anywhere in the text of a post, including its tags.
+pub const TARGET_CONTENT: &str = "content";
+/// Matches the word only as a hashtag.
+pub const TARGET_TAG: &str = "tag";
+
+/// How long a new muted word stays muted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MuteDuration {
+    #[default]
+    Forever,
+    Day,
+    Week,
+    Month,
+}
+
+impl MuteDuration {
+    pub fn next(self) -> Self {
+        match self {
+            MuteDuration::Forever => MuteDuration::Day,
+            MuteDuration::Day => MuteDuration::Week,
+            MuteDuration::Week => MuteDuration::Month,
+            MuteDuration::Month => MuteDuration::Forever,
+        }
+    }
+
+    /// When a word muted now for this long expires, `None` for never.
+    pub fn expires_at(self) -> Option<Datetime> {
+        let days = match self {
+            MuteDuration::Forever => return None,
+            MuteDuration::Day => 1,
+            MuteDuration::Week => 7,
+            MuteDuration::Month => 30,
+        };
+        let expires_at = Utc::now() + chrono::Duration::days(days);
+        Some(Datetime::new(expires_at.fixed_offset()))
+    }
+}
+
+impl fmt::Display for MuteDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let str = match self {
+            MuteDuration::Forever => "forever",
+            MuteDuration::Day => "24 hours",
+            MuteDuration::Week => "7 days",
+            MuteDuration::Month => "30 days",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// The word being added in the muted words popup.
+#[derive(Clone, Default)]
+pub struct MutedWordPrompt {
+    pub input: Input,
+    /// Whether the word is only muted as a hashtag rather than anywhere in the text.
+    pub tags_only: bool,
+    pub duration: MuteDuration,
+}
+
+impl MutedWordPrompt {
+    /// The muted word to add, or `None` if nothing was typed. A leading `#` is dropped, as tags
+    /// are matched without it.
+    pub fn muted_word(&self) -> Option<MutedWord> {
+        let value = self.input.value().trim().trim_start_matches('#').trim();
+        if value.is_empty() {
+            return None;
+        }
+        let targets = if self.tags_only {
+            vec![TARGET_TAG.to_string()]
+        } else {
+            vec![TARGET_CONTENT.to_string(), TARGET_TAG.to_string()]
+        };
+        Some(
+            MutedWordData {
+                actor_target: Some("all".to_string()),
+                expires_at: self.duration.expires_at(),
+                id: None,
+                targets,
+                value: value.to_string(),
+            }
+            .into(),
+        )
+    }
+}
+
+/// Whether `word` has expired and is no longer applied.
+pub fn is_expired(word: &MutedWord) -> bool {
+    word.expires_at
+        .as_ref()
+        .is_some_and(|expires_at| expires_at.as_ref() < &Utc::now().fixed_offset())
+}
+
+/// The first of `muted_words` that `post` matches, if any.
+pub fn matched_word(muted_words: &[MutedWord], post: &PostViewData) -> Option<String> {
+    let following = post
+        .author
+        .viewer
+        .as_ref()
+        .is_some_and(|viewer| viewer.following.is_some());
+    matched_record(muted_words, &post.record, following)
+}
+
+/// Finds the posts among `posts` that match one of `muted_words`, by URI, with the word each
+/// matched. Our own posts (`did`) are never hidden.
+pub fn hidden_posts<'a>(
+    muted_words: &[MutedWord],
+    did: &Did,
+    posts: impl IntoIterator<Item = &'a PostViewData>,
+) -> HashMap<String, String> {
+    posts
+        .into_iter()
+        .filter(|post| &post.author.did != did)
+        .filter_map(|post| Some((post.uri.clone(), matched_word(muted_words, post)?)))
+        .collect()
+}
+
+/// Finds the mentions, replies and quotes among `notifications` that match one of
+/// `muted_words`, by URI, with the word each matched.
+pub fn hidden_notifications(
+    muted_words: &[MutedWord],
+    notifications: &[Notification],
+) -> HashMap<String, String> {
+    notifications
+        .iter()
+        .filter_map(|notification| {
+            let following = notification
+                .author
+                .viewer
+                .as_ref()
+                .is_some_and(|viewer| viewer.following.is_some());
+            let word = matched_record(muted_words, &notification.record, following)?;
+            Some((notification.uri.clone(), word))
+        })
+        .collect()
+}
+
+/// The first of `muted_words` that the post `record` matches, if it is a post.
+fn matched_record(
+    muted_words: &[MutedWord],
+    record: &atrium_api::types::Unknown,
+    following: bool,
+) -> Option<String> {
+    let record = post::Record::try_from_unknown(record.clone()).ok()?;
+    muted_words
+        .iter()
+        .find(|word| {
+            // `has_muted_word` can only tell the author is followed from a `ProfileViewBasic`,
+            // which notifications do not have, so this is checked here instead.
+            let excluded = following && word.actor_target.as_deref() == Some("exclude-following");
+            !excluded
+                && has_muted_word(
+                    std::slice::from_ref(word),
+                    &record.text,
+                    record.facets.as_ref(),
+                    record.tags.as_ref(),
+                    record.langs.as_ref(),
+                    None,
+                )
+        })
+        .map(|word| word.value.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(text: &str, following: bool) -> PostViewData {
+        serde_json::from_value(serde_json::json!({
+            "uri": "at://did:plc:author/app.bsky.feed.post/1",
+            "cid": "bafyreiclp443lavogvhj3d2ob2cxbfuscni2k5jk7bebjzg7khl3esabwq",
+            "author": {
+                "did": "did:plc:author",
+                "handle": "author.bsky.social",
+                "viewer": {
+                    "following": following.then_some("at://did:plc:me/app.bsky.graph.follow/1"),
+                },
+            },
+            "record": {
+                "$type": "app.bsky.feed.post",
+                "text": text,
+                "createdAt": "2024-01-01T00:00:00.000Z",
+                "tags": ["Finale"],
+            },
+            "indexedAt": "2024-01-01T00:00:00.000Z",
+        }))
+        .unwrap()
+    }
+
+    fn word(value: &str, tags_only: bool) -> MutedWord {
+        MutedWordPrompt {
+            input: Input::new(value.to_string()),
+            tags_only,
+            duration: MuteDuration::Forever,
+        }
+        .muted_word()
+        .unwrap()
+    }
+
+    #[test]
+    fn text_and_tag_targets() {
+        let post = post("Who saw the ending? No spoilers please.", false);
+        assert_eq!(
+            matched_word(&[word("ending", false)], &post),
+            Some("ending".into())
+        );
+        assert_eq!(matched_word(&[word("ending", true)], &post), None);
+        assert_eq!(matched_word(&[word("end", false)], &post), None);
+        // Tags match either way, with or without the `#`.
+        assert_eq!(
+            matched_word(&[word("#finale", true)], &post),
+            Some("finale".into())
+        );
+    }
+
+    #[test]
+    fn expired_and_excluded_words_are_skipped() {
+        let mut expired = word("spoilers", false);
+        expired.expires_at = Some(Datetime::new(
+            (Utc::now() - chrono::Duration::days(1)).fixed_offset(),
+        ));
+        assert!(is_expired(&expired));
+        assert_eq!(matched_word(&[expired], &post("spoilers", false)), None);
+
+        let mut excluded = word("spoilers", false);
+        excluded.actor_target = Some("exclude-following".into());
+        assert_eq!(
+            matched_word(std::slice::from_ref(&excluded), &post("spoilers", true)),
+            None
+        );
+        assert_eq!(
+            matched_word(&[excluded], &post("spoilers", false)),
+            Some("spoilers".into())
+        );
+
+        let did = Did::new("did:plc:author".into()).unwrap();
+        assert!(
+            hidden_posts(&[word("spoilers", false)], &did, [&post("spoilers", false)]).is_empty()
+        );
+    }
+}
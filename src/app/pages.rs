@@ -0,0 +1,120 @@
+/// A page of a list fetched a page at a time: its position, from 0, and the cursor it is fetched
+/// with, `None` for the first page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    pub index: usize,
+    pub cursor: Option<String>,
+}
+
+impl Page {
+    pub const FIRST: Page = Page {
+        index: 0,
+        cursor: None,
+    };
+}
+
+/// Keeps track of the pages of the timeline, search results, a profile's feed or a follow list.
+///
+/// `cursors[i]` is the cursor page `i` is fetched with, and the one after the current page is
+/// the cursor of the next page, `None` at the end of the list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pages {
+    index: usize,
+    cursors: Vec<Option<String>>,
+}
+
+impl Default for Pages {
+    /// Nothing loaded yet, so there is no next page.
+    fn default() -> Self {
+        Self {
+            index: 0,
+            cursors: vec![None],
+        }
+    }
+}
+
+impl Pages {
+    /// The first page is loaded, with `next_cursor` for the page after it.
+    pub fn new(next_cursor: Option<String>) -> Self {
+        Self {
+            index: 0,
+            cursors: vec![None, next_cursor],
+        }
+    }
+
+    /// The current page, 1 for the first, as shown in titles.
+    pub fn number(&self) -> usize {
+        self.index + 1
+    }
+
+    pub fn current(&self) -> Page {
+        Page {
+            index: self.index,
+            cursor: self.cursors[self.index].clone(),
+        }
+    }
+
+    /// The page after the current one, or `None` at the end of the list.
+    pub fn next(&self) -> Option<Page> {
+        let cursor = self.cursors.get(self.index + 1).cloned().flatten()?;
+        Some(Page {
+            index: self.index + 1,
+            cursor: Some(cursor),
+        })
+    }
+
+    /// The page before the current one, or `None` on the first page.
+    pub fn prev(&self) -> Option<Page> {
+        let index = self.index.checked_sub(1)?;
+        Some(Page {
+            index,
+            cursor: self.cursors[index].clone(),
+        })
+    }
+
+    /// Makes `page` the current page once it is loaded, with `next_cursor` for the page after
+    /// it. Returns whether it was already the current page, as when reloading.
+    pub fn set_page(&mut self, page: &Page, next_cursor: Option<String>) -> bool {
+        let reloaded = page.index == self.index;
+        self.cursors.truncate(page.index);
+        self.cursors.push(page.cursor.clone());
+        self.cursors.push(next_cursor);
+        self.index = page.index;
+        reloaded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_are_tracked_by_cursor() {
+        let mut pages = Pages::default();
+        assert_eq!(
+            (pages.current(), pages.next(), pages.prev()),
+            (Page::FIRST, None, None)
+        );
+
+        assert!(pages.set_page(&Page::FIRST, Some("2".into())));
+        let second = pages.next().unwrap();
+        assert_eq!(second.cursor, Some("2".into()));
+        assert!(!pages.set_page(&second, Some("3".into())));
+        let third = pages.next().unwrap();
+        pages.set_page(&third, None);
+        assert_eq!(pages.number(), 3);
+        assert_eq!(pages.next(), None);
+
+        // Going back, the next page is the one we came from.
+        let prev = pages.prev().unwrap();
+        assert_eq!(prev, second);
+        pages.set_page(&prev, Some("3".into()));
+        assert_eq!(pages.next(), Some(third));
+
+        // Loading the first page again starts over.
+        pages.set_page(&Page::FIRST, Some("2'".into()));
+        assert_eq!(pages.number(), 1);
+        assert_eq!(pages.next().unwrap().cursor, Some("2'".into()));
+        assert_eq!(pages.prev(), None);
+    }
+}
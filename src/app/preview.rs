@@ -0,0 +1,267 @@
+use std::collections::{HashMap, VecDeque};
+
+use atrium_api::{
+    app::bsky::{
+        embed::{images, record_with_media},
+        feed::defs::{PostViewData, PostViewEmbedRefs},
+    },
+    types::Union,
+};
+use eyre::Result;
+use image::RgbImage;
+use ratatui::layout::Rect;
+use serde::{Deserialize, Serialize};
+
+/// Number of decoded thumbnails kept in memory.
+const MAX_THUMBNAILS: usize = 64;
+
+/// Longest side, in pixels, thumbnails are scaled down to when they are decoded.
+const MAX_THUMBNAIL_SIZE: u32 = 480;
+
+/// Cell size assumed when the terminal does not report its size in pixels.
+const DEFAULT_CELL_SIZE: (u32, u32) = (8, 16);
+
+/// How the image preview is drawn.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageProtocol {
+    /// Picked from the environment, see [`ImageProtocol::resolve`].
+    #[default]
+    Auto,
+    Kitty,
+    Iterm2,
+    Sixel,
+    /// Unicode half blocks coloured with the image, which works in any true colour terminal.
+    Halfblocks,
+}
+
+impl ImageProtocol {
+    /// Replaces `Auto` with the protocol the terminal is known to support.
+    ///
+    /// Multiplexers do not pass graphics through by default, so half blocks are used inside tmux
+    /// and screen.
+    pub fn resolve(self) -> Self {
+        if self != Self::Auto {
+            return self;
+        }
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        let (term, term_program) = (var("TERM"), var("TERM_PROGRAM"));
+
+        if std::env::var_os("TMUX").is_some() || term.starts_with("screen") {
+            Self::Halfblocks
+        } else if std::env::var_os("KITTY_WINDOW_ID").is_some()
+            || term == "xterm-kitty"
+            || term == "xterm-ghostty"
+        {
+            Self::Kitty
+        } else if term_program == "iTerm.app"
+            || term_program == "WezTerm"
+            || var("LC_TERMINAL") == "iTerm2"
+        {
+            Self::Iterm2
+        } else if term.contains("sixel") || term.starts_with("foot") || term == "mlterm" {
+            Self::Sixel
+        } else {
+            Self::Halfblocks
+        }
+    }
+}
+
+/// The image shown in the preview pane for a post.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewImage {
+    pub url: String,
+    /// 1-based position among the post's images.
+    pub index: usize,
+    pub count: usize,
+}
+
+/// Returns the thumbnail of the first image, video or link card attached to `post`.
+pub fn preview_image(post: &PostViewData) -> Option<PreviewImage> {
+    let single = |url: &Option<String>| {
+        url.clone().map(|url| PreviewImage {
+            url,
+            index: 1,
+            count: 1,
+        })
+    };
+    let images = |images: &[images::ViewImage]| {
+        images.first().map(|image| PreviewImage {
+            url: image.thumb.clone(),
+            index: 1,
+            count: images.len(),
+        })
+    };
+
+    match post.embed.as_ref()? {
+        Union::Refs(PostViewEmbedRefs::AppBskyEmbedImagesView(view)) => images(&view.images),
+        Union::Refs(PostViewEmbedRefs::AppBskyEmbedVideoView(view)) => single(&view.thumbnail),
+        Union::Refs(PostViewEmbedRefs::AppBskyEmbedExternalView(view)) => {
+            single(&view.external.thumb)
+        }
+        Union::Refs(PostViewEmbedRefs::AppBskyEmbedRecordWithMediaView(view)) => {
+            match &view.media {
+                Union::Refs(record_with_media::ViewMediaRefs::AppBskyEmbedImagesView(view)) => {
+                    images(&view.images)
+                }
+                Union::Refs(record_with_media::ViewMediaRefs::AppBskyEmbedVideoView(view)) => {
+                    single(&view.thumbnail)
+                }
+                Union::Refs(record_with_media::ViewMediaRefs::AppBskyEmbedExternalView(view)) => {
+                    single(&view.external.thumb)
+                }
+                Union::Unknown(_) => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+pub enum Thumbnail {
+    /// The download has been requested.
+    Loading,
+    Loaded(RgbImage),
+    Failed,
+}
+
+/// Decoded thumbnails by URL, dropping the oldest ones once [`MAX_THUMBNAILS`] are kept.
+#[derive(Default)]
+pub struct ThumbnailCache {
+    thumbnails: HashMap<String, Thumbnail>,
+    order: VecDeque<String>,
+}
+
+impl ThumbnailCache {
+    pub fn get(&self, url: &str) -> Option<&Thumbnail> {
+        self.thumbnails.get(url)
+    }
+
+    pub fn insert(&mut self, url: String, thumbnail: Thumbnail) {
+        if self.thumbnails.insert(url.clone(), thumbnail).is_none() {
+            self.order.push_back(url);
+        }
+        while self.order.len() > MAX_THUMBNAILS {
+            if let Some(url) = self.order.pop_front() {
+                self.thumbnails.remove(&url);
+            }
+        }
+    }
+}
+
+/// Downloads a thumbnail and decodes it off the async runtime.
+pub async fn fetch(client: &reqwest::Client, url: &str) -> Result<RgbImage> {
+    let bytes = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    tokio::task::spawn_blocking(move || decode(&bytes)).await?
+}
+
+fn decode(bytes: &[u8]) -> Result<RgbImage> {
+    let image = image::load_from_memory(bytes)?;
+    Ok(image
+        .thumbnail(MAX_THUMBNAIL_SIZE, MAX_THUMBNAIL_SIZE)
+        .to_rgb8())
+}
+
+/// Size of a terminal cell in pixels.
+pub fn cell_size() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size)
+            if size.columns > 0
+                && size.rows > 0
+                && size.width >= size.columns
+                && size.height >= size.rows =>
+        {
+            (
+                u32::from(size.width / size.columns),
+                u32::from(size.height / size.rows),
+            )
+        }
+        _ => DEFAULT_CELL_SIZE,
+    }
+}
+
+/// Scales an image of `width`×`height` pixels to fit `area`, keeping its aspect ratio.
+///
+/// Returns the cells the image covers, centred horizontally at the top of `area`, and its size
+/// in pixels given cells of `cell` pixels.
+pub fn fit(width: u32, height: u32, area: Rect, cell: (u32, u32)) -> (Rect, (u32, u32)) {
+    let (max_width, max_height) = (
+        u32::from(area.width) * cell.0,
+        u32::from(area.height) * cell.1,
+    );
+    if width == 0 || height == 0 || max_width == 0 || max_height == 0 {
+        return (Rect::new(area.x, area.y, 0, 0), (0, 0));
+    }
+
+    let scale = f64::min(
+        f64::from(max_width) / f64::from(width),
+        f64::from(max_height) / f64::from(height),
+    );
+    let pixels = (
+        ((f64::from(width) * scale) as u32).clamp(1, max_width),
+        ((f64::from(height) * scale) as u32).clamp(1, max_height),
+    );
+    let columns = pixels.0.div_ceil(cell.0) as u16;
+    let rows = pixels.1.div_ceil(cell.1) as u16;
+    (
+        Rect::new(area.x + (area.width - columns) / 2, area.y, columns, rows),
+        pixels,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_keeps_aspect_ratio() {
+        let area = Rect::new(10, 5, 40, 20);
+
+        // Wide image: limited by the width.
+        assert_eq!(
+            fit(800, 400, area, (8, 16)),
+            (Rect::new(10, 5, 40, 10), (320, 160))
+        );
+        // Tall image: limited by the height and centred.
+        assert_eq!(
+            fit(100, 400, area, (8, 16)),
+            (Rect::new(25, 5, 10, 20), (80, 320))
+        );
+    }
+
+    #[test]
+    fn fit_handles_empty_sizes() {
+        assert_eq!(
+            fit(0, 100, Rect::new(0, 0, 10, 10), (8, 16)),
+            (Rect::new(0, 0, 0, 0), (0, 0))
+        );
+        assert_eq!(
+            fit(100, 100, Rect::new(3, 4, 0, 10), (8, 16)),
+            (Rect::new(3, 4, 0, 0), (0, 0))
+        );
+    }
+
+    #[test]
+    fn cache_drops_oldest_thumbnails() {
+        let mut cache = ThumbnailCache::default();
+        for i in 0..=MAX_THUMBNAILS {
+            cache.insert(format!("https://cdn.example/{i}"), Thumbnail::Failed);
+        }
+        // Replacing an entry does not count as a new one.
+        cache.insert("https://cdn.example/1".into(), Thumbnail::Loading);
+
+        assert!(cache.get("https://cdn.example/0").is_none());
+        assert!(matches!(
+            cache.get("https://cdn.example/1"),
+            Some(Thumbnail::Loading)
+        ));
+        assert!(cache
+            .get(&format!("https://cdn.example/{MAX_THUMBNAILS}"))
+            .is_some());
+    }
+}
@@ -0,0 +1,76 @@
+use atrium_api::app::bsky::{
+    actor::defs::ProfileViewDetailedData,
+    feed::defs::{FeedViewPost, PostViewData},
+};
+use ratatui::widgets::ListState;
+
+use crate::app::pages::{Page, Pages};
+
+/// An account's profile and a page of its posts and reposts.
+#[derive(Clone)]
+pub struct Profile {
+    pub profile: ProfileViewDetailedData,
+    pub feed: Vec<FeedViewPost>,
+    pub pages: Pages,
+    pub list_state: ListState,
+    pub list_position: usize,
+}
+
+impl Profile {
+    pub fn new(
+        profile: ProfileViewDetailedData,
+        feed: Vec<FeedViewPost>,
+        next_cursor: Option<String>,
+    ) -> Self {
+        Self {
+            profile,
+            feed,
+            pages: Pages::new(next_cursor),
+            list_state: ListState::default().with_selected(Some(0)),
+            list_position: 0,
+        }
+    }
+
+    /// The DID the feed is loaded for, which stays the same when the handle changes.
+    pub fn actor(&self) -> String {
+        self.profile.did.to_string()
+    }
+
+    /// Shows `feed` as `page`, with `next_cursor` for the page after it.
+    pub fn set_page(&mut self, page: &Page, feed: Vec<FeedViewPost>, next_cursor: Option<String>) {
+        let reloaded = self.pages.set_page(page, next_cursor);
+        self.feed = feed;
+        self.list_position = if reloaded {
+            self.list_position.min(self.feed.len().saturating_sub(1))
+        } else {
+            0
+        };
+        self.list_state.select(Some(self.list_position));
+    }
+
+    pub fn current_post(&self) -> Option<PostViewData> {
+        self.feed
+            .get(self.list_position)
+            .map(|feed| feed.post.data.clone())
+    }
+
+    pub fn remove_post(&mut self, uri: &str) {
+        self.feed.retain(|feed| feed.post.uri != uri);
+        self.list_position = self.list_position.min(self.feed.len().saturating_sub(1));
+        self.list_state.select(Some(self.list_position));
+    }
+
+    pub fn move_scroll_up(&mut self) {
+        if self.list_position > 0 {
+            self.list_position -= 1;
+            self.list_state.select(Some(self.list_position));
+        }
+    }
+
+    pub fn move_scroll_down(&mut self) {
+        if self.list_position + 1 < self.feed.len() {
+            self.list_position += 1;
+            self.list_state.select(Some(self.list_position));
+        }
+    }
+}
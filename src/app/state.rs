@@ -1,25 +1,70 @@
-use std::{fmt, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
 
 use atrium_api::{
     app::bsky::{
-        feed::defs::{FeedViewPost, PostViewData},
+        actor::defs::{MutedWord, ViewerState, ViewerStateData},
+        feed::{
+            defs::{FeedViewPost, FeedViewPostReasonRefs, PostViewData},
+            post,
+        },
         notification::list_notifications::Notification,
     },
-    types::string::{Did, Handle},
+    types::{
+        string::{Did, Handle},
+        Union,
+    },
 };
-use bsky_sdk::BskyAgent;
+use bsky_sdk::{api::types::TryFromUnknown, BskyAgent};
+use chrono::Local;
 use ratatui::widgets::ListState;
 use tui_input::{Input, InputRequest};
 
-use crate::app::config::AppConfig;
+use crate::{
+    app::{
+        attachment::{Attachment, AttachmentPrompt, MAX_ATTACHMENTS},
+        composer::{self, Composer, ComposerRequest},
+        config::{AccountConfig, AppConfig},
+        drafts::{Draft, DraftKind},
+        follows::{FollowList, FollowListKind},
+        message::Message,
+        muted_words::{self, MutedWordPrompt},
+        pages::Pages,
+        preview::{self, ImageProtocol, PreviewImage},
+        profile::Profile,
+        thread::{Thread, ThreadPost},
+    },
+    bsky::facet::{self, FacetLink},
+};
+
+/// How many messages the message log keeps.
+const MAX_MESSAGES: usize = 100;
 
 #[derive(Clone, PartialEq, Eq)]
 pub enum Mode {
     Normal,
     Post,
+    /// Writing a thread, split into as many posts as it takes.
+    NewThread,
     Reply,
+    Quote,
     Help,
     Search,
+    Accounts,
+    Messages,
+    Links,
+    Drafts,
+    /// Typing the handle of a profile to open.
+    GoToProfile,
+    /// Asking whether to delete one of our posts.
+    DeletePost,
+    /// Asking whether to mute, unmute, block or unblock an account.
+    Moderate,
+    /// Listing, adding and removing muted words.
+    MutedWords,
 }
 
 impl fmt::Display for Mode {
@@ -27,9 +72,19 @@ impl fmt::Display for Mode {
         let str = match self {
             Mode::Normal => "Normal",
             Mode::Post => "Post",
+            Mode::NewThread => "New thread",
             Mode::Reply => "Reply",
+            Mode::Quote => "Quote",
             Mode::Help => "Help",
             Mode::Search => "Search",
+            Mode::Accounts => "Accounts",
+            Mode::Messages => "Messages",
+            Mode::Links => "Links",
+            Mode::Drafts => "Drafts",
+            Mode::GoToProfile => "Go to profile",
+            Mode::DeletePost => "Delete post",
+            Mode::Moderate => "Mute/block",
+            Mode::MutedWords => "Muted words",
         };
         write!(f, "{}", str)
     }
@@ -53,69 +108,321 @@ impl fmt::Display for Tab {
     }
 }
 
+/// A view shown over the tabs.
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone)]
+pub enum View {
+    Thread(Thread),
+    Profile(Profile),
+    Follows(FollowList),
+}
+
+/// A change to whether an account is muted or blocked, asked about before it is made.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Moderation {
+    Mute(Did, Handle),
+    Unmute(Did, Handle),
+    Block(Did, Handle),
+    /// Unblocks the account by deleting the block record with the given URI.
+    Unblock(Did, Handle, String),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LoginField {
+    Identifier,
+    Password,
+    AuthFactorToken,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Default)]
 pub enum AppState {
+    #[default]
     Init,
+    /// Shown when logging in failed, until the user logs in or quits.
+    Login {
+        account_index: usize,
+        previous_account_index: Option<usize>,
+        identifier: Input,
+        password: Input,
+        /// Present once the server has asked for the sign-in code sent by email.
+        auth_factor_token: Option<Input>,
+        focus: LoginField,
+        error: Option<String>,
+        is_loading: bool,
+    },
     Initialized {
         agent: Arc<BskyAgent>,
         timeline: Option<Vec<FeedViewPost>>,
         notifications: Option<Vec<Notification>>,
         search_results: Option<Vec<PostViewData>>,
+        /// Opened threads, profiles and follow lists, most recent last. The tabs are shown when
+        /// it is empty.
+        views: Vec<View>,
         input: Input,
+        composer: Composer,
+        /// Images attached to the post being written.
+        attachments: Vec<Attachment>,
+        /// Whether the posts of a new thread end with their position, like `1/3`.
+        number_thread_posts: bool,
+        attachment_prompt: Option<AttachmentPrompt>,
+        /// The post being replied to or quoted when it is not the selected one, as when a draft
+        /// is resumed.
+        target: Option<PostViewData>,
+        /// Unsent posts, most recent first.
+        drafts: Vec<Draft>,
+        drafts_list_state: ListState,
+        drafts_list_position: usize,
+        /// The post the delete confirmation is shown for.
+        post_to_delete: Option<PostViewData>,
+        /// The change the mute or block confirmation is shown for.
+        moderation: Option<Moderation>,
+        muted_words: Vec<MutedWord>,
+        muted_words_list_state: ListState,
+        muted_words_list_position: usize,
+        /// The word being added in the muted words popup.
+        muted_word_prompt: Option<MutedWordPrompt>,
+        /// Loaded posts and notifications that matched a muted word, by URI, with the word.
+        hidden_posts: HashMap<String, String>,
+        /// Hidden posts and notifications that were expanded to be read anyway.
+        revealed_posts: HashSet<String>,
+        /// Accounts we asked to follow whose follow record is not created yet.
+        pending_follows: HashSet<Did>,
         tl_list_state: ListState,
         tl_list_position: usize,
         notifications_list_state: ListState,
         notifications_list_position: usize,
         search_list_state: ListState,
         search_list_position: usize,
+        accounts_list_state: ListState,
+        accounts_list_position: usize,
+        account_index: usize,
+        messages: Vec<Message>,
+        messages_list_state: ListState,
+        messages_list_position: usize,
+        toast: Option<Message>,
+        links_list_state: ListState,
+        links_list_position: usize,
+        /// How the image preview is drawn, `None` when it is turned off.
+        image_protocol: Option<ImageProtocol>,
         handle: Handle,
         did: Did,
         mode: Mode,
         tab: Tab,
         config: Box<AppConfig>,
-        tl_current_cursor_index: usize,
-        cursors: Vec<Option<String>>,
-        search_current_cursor_index: usize,
-        search_cursors: Vec<Option<String>>,
+        timeline_pages: Pages,
+        search_pages: Pages,
         search_query: Option<String>,
         is_loading: bool,
     },
 }
 
 impl AppState {
-    pub fn initialized(agent: BskyAgent, handle: Handle, did: Did, config: AppConfig) -> Self {
+    pub fn initialized(
+        agent: BskyAgent,
+        handle: Handle,
+        did: Did,
+        config: AppConfig,
+        account_index: usize,
+    ) -> Self {
         let agent = Arc::new(agent);
+        let image_protocol = config
+            .image_preview
+            .then(|| config.image_protocol.resolve());
         Self::Initialized {
             agent,
             timeline: None,
             notifications: None,
             search_results: None,
+            views: Vec::new(),
             input: Input::default(),
+            composer: Composer::default(),
+            attachments: Vec::new(),
+            number_thread_posts: true,
+            attachment_prompt: None,
+            target: None,
+            drafts: Vec::new(),
+            drafts_list_state: ListState::default().with_selected(Some(0)),
+            drafts_list_position: 0,
+            post_to_delete: None,
+            moderation: None,
+            muted_words: Vec::new(),
+            muted_words_list_state: ListState::default().with_selected(Some(0)),
+            muted_words_list_position: 0,
+            muted_word_prompt: None,
+            hidden_posts: HashMap::new(),
+            revealed_posts: HashSet::new(),
+            pending_follows: HashSet::new(),
             tl_list_state: ListState::default().with_selected(Some(0)),
             tl_list_position: 0,
             notifications_list_state: ListState::default().with_selected(Some(0)),
             notifications_list_position: 0,
             search_list_state: ListState::default().with_selected(Some(0)),
             search_list_position: 0,
+            accounts_list_state: ListState::default().with_selected(Some(account_index)),
+            accounts_list_position: account_index,
+            account_index,
+            messages: Vec::new(),
+            messages_list_state: ListState::default().with_selected(Some(0)),
+            messages_list_position: 0,
+            toast: None,
+            links_list_state: ListState::default().with_selected(Some(0)),
+            links_list_position: 0,
+            image_protocol,
             handle,
             did,
             mode: Mode::Normal,
             tab: Tab::Home,
             config: Box::new(config),
-            tl_current_cursor_index: 0,
-            cursors: vec![None],
-            search_current_cursor_index: 0,
-            search_cursors: vec![None],
+            timeline_pages: Pages::default(),
+            search_pages: Pages::default(),
             search_query: None,
             is_loading: false,
         }
     }
 
+    pub fn login(
+        account_index: usize,
+        previous_account_index: Option<usize>,
+        identifier: String,
+        password: String,
+        auth_factor_required: bool,
+        error: Option<String>,
+    ) -> Self {
+        let focus = if auth_factor_required {
+            LoginField::AuthFactorToken
+        } else if identifier.is_empty() {
+            LoginField::Identifier
+        } else {
+            LoginField::Password
+        };
+        Self::Login {
+            account_index,
+            previous_account_index,
+            identifier: Input::new(identifier),
+            password: Input::new(password),
+            auth_factor_token: auth_factor_required.then(Input::default),
+            focus,
+            error,
+            is_loading: false,
+        }
+    }
+
     pub fn is_initialized(&self) -> bool {
         matches!(self, &Self::Initialized { .. })
     }
 
+    pub fn is_login(&self) -> bool {
+        matches!(self, &Self::Login { .. })
+    }
+
+    pub fn get_login_input(&self, field: LoginField) -> Option<Input> {
+        if let Self::Login {
+            identifier,
+            password,
+            auth_factor_token,
+            ..
+        } = self
+        {
+            match field {
+                LoginField::Identifier => Some(identifier.clone()),
+                LoginField::Password => Some(password.clone()),
+                LoginField::AuthFactorToken => auth_factor_token.clone(),
+            }
+        } else {
+            None
+        }
+    }
+
+    pub fn handle_login_input(&mut self, req: InputRequest) {
+        if let Self::Login {
+            identifier,
+            password,
+            auth_factor_token,
+            focus,
+            ..
+        } = self
+        {
+            let input = match focus {
+                LoginField::Identifier => Some(identifier),
+                LoginField::Password => Some(password),
+                LoginField::AuthFactorToken => auth_factor_token.as_mut(),
+            };
+            if let Some(input) = input {
+                input.handle(req);
+            }
+        }
+    }
+
+    pub fn get_login_focus(&self) -> LoginField {
+        if let Self::Login { focus, .. } = self {
+            *focus
+        } else {
+            LoginField::Identifier
+        }
+    }
+
+    pub fn move_login_focus_next(&mut self) {
+        if let Self::Login {
+            focus,
+            auth_factor_token,
+            ..
+        } = self
+        {
+            *focus = match focus {
+                LoginField::Identifier => LoginField::Password,
+                LoginField::Password if auth_factor_token.is_some() => LoginField::AuthFactorToken,
+                _ => LoginField::Identifier,
+            }
+        }
+    }
+
+    pub fn move_login_focus_prev(&mut self) {
+        if let Self::Login {
+            focus,
+            auth_factor_token,
+            ..
+        } = self
+        {
+            *focus = match focus {
+                LoginField::Identifier if auth_factor_token.is_some() => {
+                    LoginField::AuthFactorToken
+                }
+                LoginField::Identifier => LoginField::Password,
+                LoginField::Password => LoginField::Identifier,
+                LoginField::AuthFactorToken => LoginField::Password,
+            }
+        }
+    }
+
+    pub fn get_login_error(&self) -> Option<String> {
+        if let Self::Login { error, .. } = self {
+            error.clone()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_login_account_index(&self) -> usize {
+        if let Self::Login { account_index, .. } = self {
+            *account_index
+        } else {
+            0
+        }
+    }
+
+    pub fn get_previous_account_index(&self) -> Option<usize> {
+        if let Self::Login {
+            previous_account_index,
+            ..
+        } = self
+        {
+            *previous_account_index
+        } else {
+            None
+        }
+    }
+
     pub fn get_handle(&self) -> Handle {
         if let Self::Initialized { handle, .. } = self {
             handle.clone()
@@ -140,6 +447,47 @@ impl AppState {
         }
     }
 
+    pub fn get_config(&self) -> AppConfig {
+        if let Self::Initialized { config, .. } = self {
+            *config.clone()
+        } else {
+            AppConfig::default()
+        }
+    }
+
+    pub fn get_image_protocol(&self) -> Option<ImageProtocol> {
+        if let Self::Initialized { image_protocol, .. } = self {
+            *image_protocol
+        } else {
+            None
+        }
+    }
+
+    pub fn get_account_index(&self) -> usize {
+        if let Self::Initialized { account_index, .. } = self {
+            *account_index
+        } else {
+            0
+        }
+    }
+
+    pub fn get_account(&self) -> AccountConfig {
+        if let Self::Initialized {
+            config,
+            account_index,
+            ..
+        } = self
+        {
+            config
+                .accounts()
+                .get(*account_index)
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            AccountConfig::default()
+        }
+    }
+
     pub fn get_input(&self) -> Input {
         if let Self::Initialized { input, .. } = self {
             input.clone()
@@ -160,233 +508,1421 @@ impl AppState {
         }
     }
 
-    pub fn move_input_cursor_prev(&mut self) {
-        if let Self::Initialized { input, .. } = self {
-            input.handle(InputRequest::GoToPrevChar);
+    /// Empties the composer after the post was sent or put away.
+    pub fn clear_composer(&mut self) {
+        if let Self::Initialized {
+            composer,
+            attachments,
+            target,
+            ..
+        } = self
+        {
+            *composer = Composer::default();
+            attachments.clear();
+            *target = None;
         }
     }
 
-    pub fn move_input_cursor_next(&mut self) {
-        if let Self::Initialized { input, .. } = self {
-            input.handle(InputRequest::GoToNextChar);
+    pub fn set_target(&mut self, post: Option<PostViewData>) {
+        if let Self::Initialized { target, .. } = self {
+            *target = post;
         }
     }
 
-    pub fn move_input_cursor_start(&mut self) {
-        if let Self::Initialized { input, .. } = self {
-            input.handle(InputRequest::GoToStart);
+    /// Returns the post being replied to or quoted.
+    pub fn get_target_post(&self) -> Option<PostViewData> {
+        match self {
+            Self::Initialized {
+                target: Some(target),
+                ..
+            } => Some(target.clone()),
+            _ => self.get_selected_post(),
         }
     }
 
-    pub fn move_input_cursor_end(&mut self) {
-        if let Self::Initialized { input, .. } = self {
-            input.handle(InputRequest::GoToEnd);
+    /// Returns the post a new thread continues from, if it is the rest of one that stopped part
+    /// way. Unlike [`Self::get_target_post`], this is never the selected post.
+    pub fn get_thread_parent(&self) -> Option<PostViewData> {
+        if let Self::Initialized { target, .. } = self {
+            target.clone()
+        } else {
+            None
         }
     }
 
-    pub fn remove_input_prev(&mut self) {
-        if let Self::Initialized { input, .. } = self {
-            input.handle(InputRequest::DeletePrevChar);
+    pub fn get_post_to_delete(&self) -> Option<PostViewData> {
+        if let Self::Initialized { post_to_delete, .. } = self {
+            post_to_delete.clone()
+        } else {
+            None
         }
     }
 
-    pub fn move_tl_scroll_top(&mut self) {
+    pub fn set_post_to_delete(&mut self, post: Option<PostViewData>) {
+        if let Self::Initialized { post_to_delete, .. } = self {
+            *post_to_delete = post;
+        }
+    }
+
+    pub fn get_moderation(&self) -> Option<Moderation> {
+        if let Self::Initialized { moderation, .. } = self {
+            moderation.clone()
+        } else {
+            None
+        }
+    }
+
+    pub fn set_moderation(&mut self, m: Option<Moderation>) {
+        if let Self::Initialized { moderation, .. } = self {
+            *moderation = m;
+        }
+    }
+
+    pub fn get_muted_words(&self) -> Vec<MutedWord> {
+        if let Self::Initialized { muted_words, .. } = self {
+            muted_words.clone()
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn set_muted_words(&mut self, words: Vec<MutedWord>) {
         if let Self::Initialized {
-            tl_list_state,
-            tl_list_position,
+            muted_words,
+            muted_words_list_state,
+            muted_words_list_position,
             ..
         } = self
         {
-            *tl_list_position = 0;
-            tl_list_state.select(Some(0));
+            *muted_words = words;
+            *muted_words_list_position =
+                (*muted_words_list_position).min(muted_words.len().saturating_sub(1));
+            muted_words_list_state.select(Some(*muted_words_list_position));
         }
     }
 
-    pub fn move_tl_scroll_up(&mut self) {
+    pub fn move_muted_words_scroll_up(&mut self) {
         if let Self::Initialized {
-            tl_list_position,
-            tl_list_state,
+            muted_words_list_position,
+            muted_words_list_state,
             ..
         } = self
         {
-            if *tl_list_position > 0 {
-                *tl_list_position -= 1;
-                tl_list_state.select(Some(*tl_list_position));
+            if *muted_words_list_position > 0 {
+                *muted_words_list_position -= 1;
+                muted_words_list_state.select(Some(*muted_words_list_position));
             }
         }
     }
 
-    pub fn move_tl_scroll_down(&mut self) {
+    pub fn move_muted_words_scroll_down(&mut self) {
         if let Self::Initialized {
-            tl_list_position,
-            tl_list_state,
-            timeline: Some(feeds),
+            muted_words_list_position,
+            muted_words_list_state,
+            muted_words,
             ..
         } = self
         {
-            if *tl_list_position < feeds.len() - 1 {
-                *tl_list_position += 1;
-                tl_list_state.select(Some(*tl_list_position));
+            if *muted_words_list_position + 1 < muted_words.len() {
+                *muted_words_list_position += 1;
+                muted_words_list_state.select(Some(*muted_words_list_position));
             }
         }
     }
 
-    pub fn get_tl_list_position(&self) -> usize {
+    pub fn get_muted_words_list_position(&self) -> usize {
         if let Self::Initialized {
-            tl_list_position, ..
+            muted_words_list_position,
+            ..
         } = self
         {
-            *tl_list_position
+            *muted_words_list_position
         } else {
             0
         }
     }
 
-    pub fn move_notifications_scroll_up(&mut self) {
+    pub fn get_muted_words_list_state(&self) -> ListState {
         if let Self::Initialized {
-            notifications_list_position,
-            notifications_list_state,
+            muted_words_list_state,
             ..
         } = self
         {
-            if *notifications_list_position > 0 {
-                *notifications_list_position -= 1;
-                notifications_list_state.select(Some(*notifications_list_position));
-            }
+            muted_words_list_state.clone()
+        } else {
+            ListState::default()
         }
     }
 
-    pub fn move_notifications_scroll_down(&mut self) {
+    pub fn get_muted_word_prompt(&self) -> Option<MutedWordPrompt> {
         if let Self::Initialized {
-            notifications_list_position,
-            notifications_list_state,
-            notifications: Some(notifications),
-            ..
+            muted_word_prompt, ..
         } = self
         {
-            if *notifications_list_position < notifications.len() - 1 {
-                *notifications_list_position += 1;
-                notifications_list_state.select(Some(*notifications_list_position));
-            }
+            muted_word_prompt.clone()
+        } else {
+            None
         }
     }
 
-    pub fn get_notifications_list_position(&self) -> usize {
+    pub fn set_muted_word_prompt(&mut self, prompt: Option<MutedWordPrompt>) {
         if let Self::Initialized {
-            notifications_list_position,
-            ..
+            muted_word_prompt, ..
         } = self
         {
-            *notifications_list_position
-        } else {
-            0
+            *muted_word_prompt = prompt;
         }
     }
 
-    pub fn move_search_scroll_up(&mut self) {
+    /// Hides the loaded posts and notifications that match one of the muted words, behind the
+    /// word they matched. Called whenever either changes, so words that were removed or have
+    /// expired no longer hide anything.
+    pub fn update_hidden_posts(&mut self) {
         if let Self::Initialized {
-            search_list_position,
-            search_list_state,
+            did,
+            timeline,
+            search_results,
+            notifications,
+            muted_words,
+            hidden_posts,
             ..
         } = self
         {
-            if *search_list_position > 0 {
-                *search_list_position -= 1;
-                search_list_state.select(Some(*search_list_position));
-            }
+            let posts = timeline
+                .iter()
+                .flatten()
+                .map(|feed| &feed.post.data)
+                .chain(search_results.iter().flatten());
+            *hidden_posts = muted_words::hidden_posts(muted_words, did, posts);
+            hidden_posts.extend(muted_words::hidden_notifications(
+                muted_words,
+                notifications.as_deref().unwrap_or_default(),
+            ));
         }
     }
 
-    pub fn move_search_scroll_down(&mut self) {
+    /// The muted word the post or notification with `uri` is hidden behind, unless it has been
+    /// expanded.
+    pub fn get_hidden_word(&self, uri: &str) -> Option<String> {
         if let Self::Initialized {
-            search_list_position,
-            search_list_state,
-            search_results: Some(results),
+            hidden_posts,
+            revealed_posts,
             ..
         } = self
         {
-            if *search_list_position < results.len() - 1 {
-                *search_list_position += 1;
-                search_list_state.select(Some(*search_list_position));
+            if revealed_posts.contains(uri) {
+                return None;
             }
+            hidden_posts.get(uri).cloned()
+        } else {
+            None
         }
     }
 
-    pub fn get_search_list_position(&self) -> usize {
+    /// Expands the hidden post or notification with `uri`, returning whether it was hidden.
+    pub fn reveal_post(&mut self, uri: &str) -> bool {
+        if self.get_hidden_word(uri).is_none() {
+            return false;
+        }
+        if let Self::Initialized { revealed_posts, .. } = self {
+            revealed_posts.insert(uri.to_string());
+        }
+        true
+    }
+
+    /// Removes the post with `uri` from the timeline, the search results and the opened
+    /// threads and profiles, keeping the selection on the item that took its place.
+    pub fn remove_post(&mut self, uri: &str) {
         if let Self::Initialized {
+            timeline,
+            tl_list_state,
+            tl_list_position,
+            search_results,
+            search_list_state,
             search_list_position,
+            views,
             ..
         } = self
         {
-            *search_list_position
-        } else {
-            0
+            if let Some(feeds) = timeline {
+                feeds.retain(|feed| feed.post.uri != uri);
+                *tl_list_position = (*tl_list_position).min(feeds.len().saturating_sub(1));
+                tl_list_state.select(Some(*tl_list_position));
+            }
+            if let Some(results) = search_results {
+                results.retain(|post| post.uri != uri);
+                *search_list_position =
+                    (*search_list_position).min(results.len().saturating_sub(1));
+                search_list_state.select(Some(*search_list_position));
+            }
+            for view in views {
+                match view {
+                    View::Thread(thread) => thread.remove_post(uri),
+                    View::Profile(profile) => profile.remove_post(uri),
+                    View::Follows(_) => {}
+                }
+            }
         }
     }
 
-    pub fn set_timeline(&mut self, f: Option<Vec<FeedViewPost>>) {
-        if let Self::Initialized { timeline, .. } = self {
-            *timeline = f;
+    /// Returns the account `F`, `M` and `B` act on, with our relationship to it.
+    ///
+    /// That is the account whose profile is shown, the one selected in a follow list, the author
+    /// of the selected notification, or else the author of the selected post.
+    pub fn get_selected_account(&self) -> Option<(Did, Handle, Option<ViewerState>)> {
+        if let Some(profile) = self.get_profile() {
+            let profile = profile.profile;
+            return Some((profile.did, profile.handle, profile.viewer));
         }
-    }
-
-    pub fn get_timeline(&self) -> Option<Vec<FeedViewPost>> {
-        if let Self::Initialized { timeline, .. } = self {
-            timeline.clone()
-        } else {
-            None
+        if let Some(list) = self.get_follow_list() {
+            let account = list.current_account()?;
+            return Some((account.did, account.handle, account.viewer));
         }
-    }
-
-    pub fn set_search_results(&mut self, f: Option<Vec<PostViewData>>) {
-        if let Self::Initialized { search_results, .. } = self {
-            *search_results = f;
+        if !self.is_view_open() && self.get_tab() == Tab::Notifications {
+            let author = self.get_current_notification()?.data.author;
+            return Some((
+                author.did.clone(),
+                author.handle.clone(),
+                author.viewer.clone(),
+            ));
         }
+        let author = self.get_selected_post()?.author;
+        Some((
+            author.did.clone(),
+            author.handle.clone(),
+            author.viewer.clone(),
+        ))
+    }
+
+    /// Records whether we follow `did` everywhere the account is shown, so that the change is
+    /// seen before the timeline or profile is reloaded.
+    pub fn set_following(&mut self, did: &Did, following: Option<String>) {
+        if let Self::Initialized { views, .. } = self {
+            for view in views.iter_mut() {
+                if let View::Profile(profile) = view {
+                    let profile = &mut profile.profile;
+                    if &profile.did == did {
+                        let was_following = profile
+                            .viewer
+                            .as_ref()
+                            .is_some_and(|viewer| viewer.following.is_some());
+                        let count = profile.followers_count.unwrap_or(0);
+                        profile.followers_count =
+                            Some(match (was_following, following.is_some()) {
+                                (false, true) => count + 1,
+                                (true, false) => count - 1,
+                                _ => count,
+                            });
+                    }
+                }
+            }
+        }
+        self.update_viewer(did, |viewer| viewer.following = following.clone());
     }
 
-    pub fn get_search_results(&self) -> Option<Vec<PostViewData>> {
-        if let Self::Initialized { search_results, .. } = self {
-            search_results.clone()
-        } else {
-            None
-        }
+    /// Whether we follow `did`, or asked to and are waiting for the follow record, given its
+    /// `viewer` state.
+    pub fn is_following(&self, did: &Did, viewer: Option<&ViewerState>) -> bool {
+        viewer.is_some_and(|viewer| viewer.following.is_some()) || self.is_follow_pending(did)
     }
 
-    pub fn get_search_list_state(&self) -> ListState {
+    pub fn is_follow_pending(&self, did: &Did) -> bool {
         if let Self::Initialized {
-            search_list_state, ..
+            pending_follows, ..
         } = self
         {
-            search_list_state.clone()
+            pending_follows.contains(did)
         } else {
-            ListState::default()
+            false
         }
     }
 
-    pub fn move_search_scroll_top(&mut self) {
+    pub fn set_follow_pending(&mut self, did: &Did, pending: bool) {
         if let Self::Initialized {
-            search_list_state,
-            search_list_position,
-            ..
+            pending_follows, ..
         } = self
         {
-            *search_list_position = 0;
-            search_list_state.select(Some(0));
+            if pending {
+                pending_follows.insert(did.clone());
+            } else {
+                pending_follows.remove(did);
+            }
+        }
+    }
+
+    /// Records whether we muted `did` everywhere the account is shown, and drops it from our
+    /// list of mutes once unmuted.
+    pub fn set_muted(&mut self, did: &Did, muted: bool) {
+        self.update_viewer(did, |viewer| viewer.muted = Some(muted));
+        if !muted {
+            self.remove_from_account_lists(FollowListKind::Mutes, did);
+        }
+    }
+
+    /// Records whether we block `did` everywhere the account is shown, and drops it from our
+    /// list of blocks once unblocked.
+    pub fn set_blocking(&mut self, did: &Did, blocking: Option<String>) {
+        let unblocked = blocking.is_none();
+        self.update_viewer(did, |viewer| viewer.blocking = blocking.clone());
+        if unblocked {
+            self.remove_from_account_lists(FollowListKind::Blocks, did);
+        }
+    }
+
+    /// Removes the posts and reposts of `did` from the timeline and the search results, its
+    /// notifications, the opened threads and the feeds of the other opened profiles, as when it
+    /// is muted or blocked.
+    pub fn remove_author(&mut self, did: &Did) {
+        if let Self::Initialized {
+            timeline,
+            tl_list_state,
+            tl_list_position,
+            search_results,
+            search_list_state,
+            search_list_position,
+            notifications,
+            notifications_list_state,
+            notifications_list_position,
+            views,
+            ..
+        } = self
+        {
+            let is_by_author = |feed: &FeedViewPost| {
+                &feed.post.author.did == did
+                    || matches!(
+                        &feed.reason,
+                        Some(Union::Refs(FeedViewPostReasonRefs::ReasonRepost(repost)))
+                            if &repost.by.did == did
+                    )
+            };
+            if let Some(feeds) = timeline {
+                feeds.retain(|feed| !is_by_author(feed));
+                *tl_list_position = (*tl_list_position).min(feeds.len().saturating_sub(1));
+                tl_list_state.select(Some(*tl_list_position));
+            }
+            if let Some(results) = search_results {
+                results.retain(|post| &post.author.did != did);
+                *search_list_position =
+                    (*search_list_position).min(results.len().saturating_sub(1));
+                search_list_state.select(Some(*search_list_position));
+            }
+            if let Some(notifications) = notifications {
+                notifications.retain(|notification| &notification.author.did != did);
+                *notifications_list_position =
+                    (*notifications_list_position).min(notifications.len().saturating_sub(1));
+                notifications_list_state.select(Some(*notifications_list_position));
+            }
+            for view in views {
+                if let View::Thread(thread) = view {
+                    thread.remove_author(did);
+                } else if let View::Profile(profile) = view {
+                    if &profile.profile.did != did {
+                        let uris: Vec<String> = profile
+                            .feed
+                            .iter()
+                            .filter(|feed| is_by_author(feed))
+                            .map(|feed| feed.post.uri.clone())
+                            .collect();
+                        for uri in uris {
+                            profile.remove_post(&uri);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Calls `f` with our relationship to `did` wherever the account is shown.
+    fn update_viewer(&mut self, did: &Did, f: impl Fn(&mut ViewerStateData)) {
+        if let Self::Initialized {
+            timeline,
+            search_results,
+            notifications,
+            views,
+            ..
+        } = self
+        {
+            let mut posts: Vec<&mut PostViewData> = Vec::new();
+            for feed in timeline.iter_mut().flatten() {
+                posts.push(&mut feed.post);
+            }
+            posts.extend(search_results.iter_mut().flatten());
+            for view in views.iter_mut() {
+                match view {
+                    View::Thread(thread) => {
+                        for item in &mut thread.items {
+                            if let ThreadPost::Post(post) = &mut item.post {
+                                posts.push(post);
+                            }
+                        }
+                    }
+                    View::Profile(profile) => {
+                        for feed in &mut profile.feed {
+                            posts.push(&mut feed.post);
+                        }
+                        if &profile.profile.did == did {
+                            f(viewer_mut(&mut profile.profile.viewer));
+                        }
+                    }
+                    View::Follows(list) => {
+                        for account in &mut list.accounts {
+                            if &account.did == did {
+                                f(viewer_mut(&mut account.viewer));
+                            }
+                        }
+                    }
+                }
+            }
+            for post in posts {
+                if &post.author.did == did {
+                    f(viewer_mut(&mut post.author.viewer));
+                }
+            }
+            for notification in notifications.iter_mut().flatten() {
+                if &notification.author.did == did {
+                    f(viewer_mut(&mut notification.author.viewer));
+                }
+            }
+        }
+    }
+
+    fn remove_from_account_lists(&mut self, kind: FollowListKind, did: &Did) {
+        if let Self::Initialized { views, .. } = self {
+            for view in views {
+                if let View::Follows(list) = view {
+                    if list.kind == kind {
+                        list.remove_account(did.as_str());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns what is being written as a draft, or `None` if there is nothing to keep.
+    pub fn get_draft(&self) -> Option<Draft> {
+        let uri = || self.get_target_post().map(|post| post.uri);
+        let kind = match self.get_mode() {
+            Mode::Post => DraftKind::Post,
+            Mode::NewThread => match self.get_thread_parent() {
+                Some(parent) => DraftKind::ThreadReply { uri: parent.uri },
+                None => DraftKind::Thread,
+            },
+            Mode::Reply => DraftKind::Reply { uri: uri()? },
+            Mode::Quote => DraftKind::Quote { uri: uri()? },
+            _ => return None,
+        };
+        let draft = Draft {
+            kind,
+            text: self.get_composer().text().to_string(),
+            attachments: self.get_attachments(),
+            saved_at: Local::now(),
+        };
+        (!draft.is_empty()).then_some(draft)
+    }
+
+    pub fn get_drafts(&self) -> Vec<Draft> {
+        if let Self::Initialized { drafts, .. } = self {
+            drafts.clone()
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn set_drafts(&mut self, d: Vec<Draft>) {
+        if let Self::Initialized { drafts, .. } = self {
+            *drafts = d;
+        }
+    }
+
+    /// Keeps `draft` as the most recent one.
+    pub fn push_draft(&mut self, draft: Draft) {
+        if let Self::Initialized { drafts, .. } = self {
+            drafts.insert(0, draft);
+        }
+    }
+
+    pub fn remove_draft(&mut self, index: usize) -> Option<Draft> {
+        if let Self::Initialized {
+            drafts,
+            drafts_list_position,
+            drafts_list_state,
+            ..
+        } = self
+        {
+            if index >= drafts.len() {
+                return None;
+            }
+            let draft = drafts.remove(index);
+            *drafts_list_position = (*drafts_list_position).min(drafts.len().saturating_sub(1));
+            drafts_list_state.select(Some(*drafts_list_position));
+            Some(draft)
+        } else {
+            None
+        }
+    }
+
+    pub fn move_drafts_scroll_up(&mut self) {
+        if let Self::Initialized {
+            drafts_list_position,
+            drafts_list_state,
+            ..
+        } = self
+        {
+            if *drafts_list_position > 0 {
+                *drafts_list_position -= 1;
+                drafts_list_state.select(Some(*drafts_list_position));
+            }
+        }
+    }
+
+    pub fn move_drafts_scroll_down(&mut self) {
+        if let Self::Initialized {
+            drafts_list_position,
+            drafts_list_state,
+            drafts,
+            ..
+        } = self
+        {
+            if *drafts_list_position + 1 < drafts.len() {
+                *drafts_list_position += 1;
+                drafts_list_state.select(Some(*drafts_list_position));
+            }
+        }
+    }
+
+    pub fn move_drafts_scroll_top(&mut self) {
+        if let Self::Initialized {
+            drafts_list_position,
+            drafts_list_state,
+            ..
+        } = self
+        {
+            *drafts_list_position = 0;
+            drafts_list_state.select(Some(0));
+        }
+    }
+
+    pub fn get_drafts_list_position(&self) -> usize {
+        if let Self::Initialized {
+            drafts_list_position,
+            ..
+        } = self
+        {
+            *drafts_list_position
+        } else {
+            0
+        }
+    }
+
+    pub fn get_drafts_list_state(&self) -> ListState {
+        if let Self::Initialized {
+            drafts_list_state, ..
+        } = self
+        {
+            drafts_list_state.clone()
+        } else {
+            ListState::default()
+        }
+    }
+
+    pub fn get_number_thread_posts(&self) -> bool {
+        if let Self::Initialized {
+            number_thread_posts,
+            ..
+        } = self
+        {
+            *number_thread_posts
+        } else {
+            true
+        }
+    }
+
+    pub fn toggle_number_thread_posts(&mut self) {
+        if let Self::Initialized {
+            number_thread_posts,
+            ..
+        } = self
+        {
+            *number_thread_posts = !*number_thread_posts;
+        }
+    }
+
+    /// The posts the text being written is sent as in a new thread.
+    pub fn get_thread_posts(&self) -> Vec<String> {
+        composer::split_thread(self.get_composer().text(), self.get_number_thread_posts())
+    }
+
+    pub fn get_composer(&self) -> Composer {
+        if let Self::Initialized { composer, .. } = self {
+            composer.clone()
+        } else {
+            Composer::default()
+        }
+    }
+
+    pub fn set_composer(&mut self, c: Composer) {
+        if let Self::Initialized { composer, .. } = self {
+            *composer = c;
+        }
+    }
+
+    pub fn handle_composer_input(&mut self, req: ComposerRequest) {
+        if let Self::Initialized { composer, .. } = self {
+            composer.handle(req);
+        }
+    }
+
+    pub fn move_input_cursor_prev(&mut self) {
+        if let Self::Initialized { input, .. } = self {
+            input.handle(InputRequest::GoToPrevChar);
+        }
+    }
+
+    pub fn move_input_cursor_next(&mut self) {
+        if let Self::Initialized { input, .. } = self {
+            input.handle(InputRequest::GoToNextChar);
+        }
+    }
+
+    pub fn move_input_cursor_start(&mut self) {
+        if let Self::Initialized { input, .. } = self {
+            input.handle(InputRequest::GoToStart);
+        }
+    }
+
+    pub fn move_input_cursor_end(&mut self) {
+        if let Self::Initialized { input, .. } = self {
+            input.handle(InputRequest::GoToEnd);
+        }
+    }
+
+    pub fn remove_input_prev(&mut self) {
+        if let Self::Initialized { input, .. } = self {
+            input.handle(InputRequest::DeletePrevChar);
+        }
+    }
+
+    pub fn get_attachments(&self) -> Vec<Attachment> {
+        if let Self::Initialized { attachments, .. } = self {
+            attachments.clone()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Removes and returns the attachments of the post being written.
+    pub fn take_attachments(&mut self) -> Vec<Attachment> {
+        if let Self::Initialized { attachments, .. } = self {
+            std::mem::take(attachments)
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn set_attachments(&mut self, a: Vec<Attachment>) {
+        if let Self::Initialized { attachments, .. } = self {
+            *attachments = a;
+        }
+    }
+
+    /// Attaches an image, returning `false` if the post already has as many as it can.
+    pub fn add_attachment(&mut self, attachment: Attachment) -> bool {
+        if let Self::Initialized { attachments, .. } = self {
+            if attachments.len() < MAX_ATTACHMENTS {
+                attachments.push(attachment);
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn remove_last_attachment(&mut self) {
+        if let Self::Initialized { attachments, .. } = self {
+            attachments.pop();
+        }
+    }
+
+    pub fn set_last_attachment_alt(&mut self, alt: String) {
+        if let Self::Initialized { attachments, .. } = self {
+            if let Some(attachment) = attachments.last_mut() {
+                attachment.alt = alt;
+            }
+        }
+    }
+
+    pub fn get_attachment_prompt(&self) -> Option<AttachmentPrompt> {
+        if let Self::Initialized {
+            attachment_prompt, ..
+        } = self
+        {
+            attachment_prompt.clone()
+        } else {
+            None
+        }
+    }
+
+    pub fn set_attachment_prompt(&mut self, prompt: Option<AttachmentPrompt>) {
+        if let Self::Initialized {
+            attachment_prompt, ..
+        } = self
+        {
+            *attachment_prompt = prompt;
+        }
+    }
+
+    pub fn handle_attachment_prompt_input(&mut self, req: InputRequest) {
+        if let Self::Initialized {
+            attachment_prompt: Some(prompt),
+            ..
+        } = self
+        {
+            prompt.input_mut().handle(req);
+        }
+    }
+
+    pub fn move_tl_scroll_top(&mut self) {
+        if let Self::Initialized {
+            tl_list_state,
+            tl_list_position,
+            ..
+        } = self
+        {
+            *tl_list_position = 0;
+            tl_list_state.select(Some(0));
+        }
+    }
+
+    pub fn move_tl_scroll_up(&mut self) {
+        if let Self::Initialized {
+            tl_list_position,
+            tl_list_state,
+            ..
+        } = self
+        {
+            if *tl_list_position > 0 {
+                *tl_list_position -= 1;
+                tl_list_state.select(Some(*tl_list_position));
+            }
+        }
+    }
+
+    pub fn move_tl_scroll_down(&mut self) {
+        if let Self::Initialized {
+            tl_list_position,
+            tl_list_state,
+            timeline: Some(feeds),
+            ..
+        } = self
+        {
+            if *tl_list_position + 1 < feeds.len() {
+                *tl_list_position += 1;
+                tl_list_state.select(Some(*tl_list_position));
+            }
+        }
+    }
+
+    pub fn get_tl_list_position(&self) -> usize {
+        if let Self::Initialized {
+            tl_list_position, ..
+        } = self
+        {
+            *tl_list_position
+        } else {
+            0
+        }
+    }
+
+    pub fn move_notifications_scroll_up(&mut self) {
+        if let Self::Initialized {
+            notifications_list_position,
+            notifications_list_state,
+            ..
+        } = self
+        {
+            if *notifications_list_position > 0 {
+                *notifications_list_position -= 1;
+                notifications_list_state.select(Some(*notifications_list_position));
+            }
+        }
+    }
+
+    pub fn move_notifications_scroll_down(&mut self) {
+        if let Self::Initialized {
+            notifications_list_position,
+            notifications_list_state,
+            notifications: Some(notifications),
+            ..
+        } = self
+        {
+            if *notifications_list_position + 1 < notifications.len() {
+                *notifications_list_position += 1;
+                notifications_list_state.select(Some(*notifications_list_position));
+            }
+        }
+    }
+
+    pub fn get_notifications_list_position(&self) -> usize {
+        if let Self::Initialized {
+            notifications_list_position,
+            ..
+        } = self
+        {
+            *notifications_list_position
+        } else {
+            0
+        }
+    }
+
+    pub fn move_search_scroll_up(&mut self) {
+        if let Self::Initialized {
+            search_list_position,
+            search_list_state,
+            ..
+        } = self
+        {
+            if *search_list_position > 0 {
+                *search_list_position -= 1;
+                search_list_state.select(Some(*search_list_position));
+            }
+        }
+    }
+
+    pub fn move_search_scroll_down(&mut self) {
+        if let Self::Initialized {
+            search_list_position,
+            search_list_state,
+            search_results: Some(results),
+            ..
+        } = self
+        {
+            if *search_list_position + 1 < results.len() {
+                *search_list_position += 1;
+                search_list_state.select(Some(*search_list_position));
+            }
+        }
+    }
+
+    pub fn get_search_list_position(&self) -> usize {
+        if let Self::Initialized {
+            search_list_position,
+            ..
+        } = self
+        {
+            *search_list_position
+        } else {
+            0
+        }
+    }
+
+    pub fn move_accounts_scroll_up(&mut self) {
+        if let Self::Initialized {
+            accounts_list_position,
+            accounts_list_state,
+            ..
+        } = self
+        {
+            if *accounts_list_position > 0 {
+                *accounts_list_position -= 1;
+                accounts_list_state.select(Some(*accounts_list_position));
+            }
+        }
+    }
+
+    pub fn move_accounts_scroll_down(&mut self) {
+        if let Self::Initialized {
+            accounts_list_position,
+            accounts_list_state,
+            config,
+            ..
+        } = self
+        {
+            if *accounts_list_position + 1 < config.accounts().len() {
+                *accounts_list_position += 1;
+                accounts_list_state.select(Some(*accounts_list_position));
+            }
+        }
+    }
+
+    pub fn get_accounts_list_position(&self) -> usize {
+        if let Self::Initialized {
+            accounts_list_position,
+            ..
+        } = self
+        {
+            *accounts_list_position
+        } else {
+            0
+        }
+    }
+
+    pub fn get_accounts_list_state(&self) -> ListState {
+        if let Self::Initialized {
+            accounts_list_state,
+            ..
+        } = self
+        {
+            accounts_list_state.clone()
+        } else {
+            ListState::default()
+        }
+    }
+
+    /// Shows `message` as a toast and records it in the message log.
+    pub fn push_message(&mut self, message: Message) {
+        if let Self::Initialized {
+            messages, toast, ..
+        } = self
+        {
+            messages.push(message.clone());
+            if messages.len() > MAX_MESSAGES {
+                messages.remove(0);
+            }
+            *toast = Some(message);
+        }
+    }
+
+    /// Returns the message log, newest first.
+    pub fn get_messages(&self) -> Vec<Message> {
+        if let Self::Initialized { messages, .. } = self {
+            messages.iter().rev().cloned().collect()
+        } else {
+            vec![]
+        }
+    }
+
+    pub fn get_toast(&self) -> Option<Message> {
+        if let Self::Initialized { toast, .. } = self {
+            toast.clone()
+        } else {
+            None
+        }
+    }
+
+    pub fn dismiss_toast(&mut self) {
+        if let Self::Initialized { toast, .. } = self {
+            *toast = None;
+        }
+    }
+
+    pub fn expire_toast(&mut self) {
+        if let Self::Initialized { toast, .. } = self {
+            if toast.as_ref().is_some_and(Message::is_expired) {
+                *toast = None;
+            }
+        }
+    }
+
+    pub fn move_messages_scroll_up(&mut self) {
+        if let Self::Initialized {
+            messages_list_position,
+            messages_list_state,
+            ..
+        } = self
+        {
+            if *messages_list_position > 0 {
+                *messages_list_position -= 1;
+                messages_list_state.select(Some(*messages_list_position));
+            }
+        }
+    }
+
+    pub fn move_messages_scroll_down(&mut self) {
+        if let Self::Initialized {
+            messages_list_position,
+            messages_list_state,
+            messages,
+            ..
+        } = self
+        {
+            if *messages_list_position + 1 < messages.len() {
+                *messages_list_position += 1;
+                messages_list_state.select(Some(*messages_list_position));
+            }
+        }
+    }
+
+    pub fn move_messages_scroll_top(&mut self) {
+        if let Self::Initialized {
+            messages_list_position,
+            messages_list_state,
+            ..
+        } = self
+        {
+            *messages_list_position = 0;
+            messages_list_state.select(Some(0));
+        }
+    }
+
+    pub fn get_messages_list_state(&self) -> ListState {
+        if let Self::Initialized {
+            messages_list_state,
+            ..
+        } = self
+        {
+            messages_list_state.clone()
+        } else {
+            ListState::default()
+        }
+    }
+
+    pub fn set_timeline(&mut self, f: Option<Vec<FeedViewPost>>) {
+        if let Self::Initialized { timeline, .. } = self {
+            *timeline = f;
+        }
+    }
+
+    pub fn get_timeline(&self) -> Option<Vec<FeedViewPost>> {
+        if let Self::Initialized { timeline, .. } = self {
+            timeline.clone()
+        } else {
+            None
+        }
+    }
+
+    pub fn set_search_results(&mut self, f: Option<Vec<PostViewData>>) {
+        if let Self::Initialized { search_results, .. } = self {
+            *search_results = f;
+        }
+    }
+
+    pub fn get_search_results(&self) -> Option<Vec<PostViewData>> {
+        if let Self::Initialized { search_results, .. } = self {
+            search_results.clone()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_search_list_state(&self) -> ListState {
+        if let Self::Initialized {
+            search_list_state, ..
+        } = self
+        {
+            search_list_state.clone()
+        } else {
+            ListState::default()
+        }
+    }
+
+    pub fn move_search_scroll_top(&mut self) {
+        if let Self::Initialized {
+            search_list_state,
+            search_list_position,
+            ..
+        } = self
+        {
+            *search_list_position = 0;
+            search_list_state.select(Some(0));
         }
     }
 
     pub fn get_current_search_result(&self) -> Option<PostViewData> {
         if let Self::Initialized {
-            search_results,
-            search_list_position,
+            search_results,
+            search_list_position,
+            ..
+        } = self
+        {
+            search_results
+                .clone()
+                .and_then(|f| f.get(*search_list_position).cloned())
+        } else {
+            None
+        }
+    }
+
+    pub fn push_thread(&mut self, thread: Thread) {
+        if let Self::Initialized { views, .. } = self {
+            views.push(View::Thread(thread));
+        }
+    }
+
+    pub fn push_profile(&mut self, profile: Profile) {
+        if let Self::Initialized { views, .. } = self {
+            views.push(View::Profile(profile));
+        }
+    }
+
+    /// Closes the current thread, profile or follow list, going back to the previous one or to
+    /// the tabs.
+    pub fn pop_view(&mut self) {
+        if let Self::Initialized { views, .. } = self {
+            views.pop();
+        }
+    }
+
+    /// Whether a thread, profile or follow list is shown instead of the tabs.
+    pub fn is_view_open(&self) -> bool {
+        if let Self::Initialized { views, .. } = self {
+            !views.is_empty()
+        } else {
+            false
+        }
+    }
+
+    pub fn get_thread(&self) -> Option<Thread> {
+        if let Self::Initialized { views, .. } = self {
+            match views.last() {
+                Some(View::Thread(thread)) => Some(thread.clone()),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    pub fn set_thread(&mut self, thread: Thread) {
+        if let Self::Initialized { views, .. } = self {
+            if let Some(View::Thread(current)) = views.last_mut() {
+                *current = thread;
+            }
+        }
+    }
+
+    pub fn is_thread_view(&self) -> bool {
+        if let Self::Initialized { views, .. } = self {
+            matches!(views.last(), Some(View::Thread(_)))
+        } else {
+            false
+        }
+    }
+
+    pub fn get_current_thread_post(&self) -> Option<PostViewData> {
+        if let Self::Initialized { views, .. } = self {
+            match views.last() {
+                Some(View::Thread(thread)) => thread.current_post(),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    pub fn move_thread_scroll_up(&mut self) {
+        if let Self::Initialized { views, .. } = self {
+            if let Some(View::Thread(thread)) = views.last_mut() {
+                thread.move_scroll_up();
+            }
+        }
+    }
+
+    pub fn move_thread_scroll_down(&mut self) {
+        if let Self::Initialized { views, .. } = self {
+            if let Some(View::Thread(thread)) = views.last_mut() {
+                thread.move_scroll_down();
+            }
+        }
+    }
+
+    pub fn get_profile(&self) -> Option<Profile> {
+        if let Self::Initialized { views, .. } = self {
+            match views.last() {
+                Some(View::Profile(profile)) => Some(profile.clone()),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Calls `f` with the open profile of `actor`, the topmost if there are several. A page
+    /// that was fetched goes into the profile as it is now, so that what changed while it was
+    /// loading is kept, and is dropped if the profile was closed meanwhile.
+    pub fn update_profile(&mut self, actor: &str, f: impl FnOnce(&mut Profile)) {
+        if let Self::Initialized { views, .. } = self {
+            let profile = views.iter_mut().rev().find_map(|view| match view {
+                View::Profile(profile) if profile.actor() == actor => Some(profile),
+                _ => None,
+            });
+            if let Some(profile) = profile {
+                f(profile);
+            }
+        }
+    }
+
+    pub fn is_profile_view(&self) -> bool {
+        if let Self::Initialized { views, .. } = self {
+            matches!(views.last(), Some(View::Profile(_)))
+        } else {
+            false
+        }
+    }
+
+    pub fn get_current_profile_post(&self) -> Option<PostViewData> {
+        if let Self::Initialized { views, .. } = self {
+            match views.last() {
+                Some(View::Profile(profile)) => profile.current_post(),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    pub fn move_profile_scroll_up(&mut self) {
+        if let Self::Initialized { views, .. } = self {
+            if let Some(View::Profile(profile)) = views.last_mut() {
+                profile.move_scroll_up();
+            }
+        }
+    }
+
+    pub fn move_profile_scroll_down(&mut self) {
+        if let Self::Initialized { views, .. } = self {
+            if let Some(View::Profile(profile)) = views.last_mut() {
+                profile.move_scroll_down();
+            }
+        }
+    }
+
+    pub fn push_follow_list(&mut self, list: FollowList) {
+        if let Self::Initialized { views, .. } = self {
+            views.push(View::Follows(list));
+        }
+    }
+
+    pub fn get_follow_list(&self) -> Option<FollowList> {
+        if let Self::Initialized { views, .. } = self {
+            match views.last() {
+                Some(View::Follows(list)) => Some(list.clone()),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    pub fn set_follow_list(&mut self, list: FollowList) {
+        if let Self::Initialized { views, .. } = self {
+            if let Some(View::Follows(current)) = views.last_mut() {
+                *current = list;
+            }
+        }
+    }
+
+    /// Calls `f` with the open follow list of `kind` for `actor`, like [`Self::update_profile`].
+    pub fn update_follow_list(
+        &mut self,
+        kind: FollowListKind,
+        actor: &str,
+        f: impl FnOnce(&mut FollowList),
+    ) {
+        if let Self::Initialized { views, .. } = self {
+            let list = views.iter_mut().rev().find_map(|view| match view {
+                View::Follows(list) if list.kind == kind && list.actor() == actor => Some(list),
+                _ => None,
+            });
+            if let Some(list) = list {
+                f(list);
+            }
+        }
+    }
+
+    pub fn is_follow_list_view(&self) -> bool {
+        if let Self::Initialized { views, .. } = self {
+            matches!(views.last(), Some(View::Follows(_)))
+        } else {
+            false
+        }
+    }
+
+    /// Returns the post the cursor is on in the thread or profile view or the current tab.
+    pub fn get_selected_post(&self) -> Option<PostViewData> {
+        if self.is_thread_view() {
+            return self.get_current_thread_post();
+        }
+        if self.is_profile_view() {
+            return self.get_current_profile_post();
+        }
+        if self.is_view_open() {
+            return None;
+        }
+        match self.get_tab() {
+            Tab::Home => self.get_current_feed().map(|feed| feed.post.data.clone()),
+            Tab::Search => self.get_current_search_result(),
+            Tab::Notifications => None,
+        }
+    }
+
+    /// Returns the image shown in the preview pane, if the preview is turned on.
+    pub fn get_preview_image(&self) -> Option<PreviewImage> {
+        self.get_image_protocol()?;
+        let post = self.get_selected_post()?;
+        if !self.is_view_open() && self.get_hidden_word(&post.uri).is_some() {
+            return None;
+        }
+        preview::preview_image(&post)
+    }
+
+    /// Returns the links, mentions and tags in the selected post.
+    pub fn get_selected_post_links(&self) -> Vec<FacetLink> {
+        self.get_selected_post()
+            .and_then(|post| post::Record::try_from_unknown(post.record).ok())
+            .map(|record| facet::links(&record.text, record.facets.clone()))
+            .unwrap_or_default()
+    }
+
+    pub fn move_links_scroll_up(&mut self) {
+        if let Self::Initialized {
+            links_list_position,
+            links_list_state,
             ..
         } = self
         {
-            search_results
-                .clone()
-                .and_then(|f| f.get(*search_list_position).cloned())
+            if *links_list_position > 0 {
+                *links_list_position -= 1;
+                links_list_state.select(Some(*links_list_position));
+            }
+        }
+    }
+
+    pub fn move_links_scroll_down(&mut self) {
+        let len = self.get_selected_post_links().len();
+        if let Self::Initialized {
+            links_list_position,
+            links_list_state,
+            ..
+        } = self
+        {
+            if *links_list_position + 1 < len {
+                *links_list_position += 1;
+                links_list_state.select(Some(*links_list_position));
+            }
+        }
+    }
+
+    pub fn move_links_scroll_top(&mut self) {
+        if let Self::Initialized {
+            links_list_position,
+            links_list_state,
+            ..
+        } = self
+        {
+            *links_list_position = 0;
+            links_list_state.select(Some(0));
+        }
+    }
+
+    pub fn get_links_list_position(&self) -> usize {
+        if let Self::Initialized {
+            links_list_position,
+            ..
+        } = self
+        {
+            *links_list_position
         } else {
-            None
+            0
+        }
+    }
+
+    pub fn get_links_list_state(&self) -> ListState {
+        if let Self::Initialized {
+            links_list_state, ..
+        } = self
+        {
+            links_list_state.clone()
+        } else {
+            ListState::default()
         }
     }
 
@@ -412,6 +1948,46 @@ impl AppState {
         }
     }
 
+    pub fn is_drafts_mode(&self) -> bool {
+        if let Self::Initialized { mode, .. } = self {
+            matches!(mode, Mode::Drafts)
+        } else {
+            false
+        }
+    }
+
+    pub fn is_go_to_profile_mode(&self) -> bool {
+        if let Self::Initialized { mode, .. } = self {
+            matches!(mode, Mode::GoToProfile)
+        } else {
+            false
+        }
+    }
+
+    pub fn is_delete_post_mode(&self) -> bool {
+        if let Self::Initialized { mode, .. } = self {
+            matches!(mode, Mode::DeletePost)
+        } else {
+            false
+        }
+    }
+
+    pub fn is_moderate_mode(&self) -> bool {
+        if let Self::Initialized { mode, .. } = self {
+            matches!(mode, Mode::Moderate)
+        } else {
+            false
+        }
+    }
+
+    pub fn is_muted_words_mode(&self) -> bool {
+        if let Self::Initialized { mode, .. } = self {
+            matches!(mode, Mode::MutedWords)
+        } else {
+            false
+        }
+    }
+
     pub fn is_post_mode(&self) -> bool {
         if let Self::Initialized { mode, .. } = self {
             matches!(mode, Mode::Post)
@@ -420,6 +1996,14 @@ impl AppState {
         }
     }
 
+    pub fn is_new_thread_mode(&self) -> bool {
+        if let Self::Initialized { mode, .. } = self {
+            matches!(mode, Mode::NewThread)
+        } else {
+            false
+        }
+    }
+
     pub fn is_reply_mode(&self) -> bool {
         if let Self::Initialized { mode, .. } = self {
             matches!(mode, Mode::Reply)
@@ -428,6 +2012,14 @@ impl AppState {
         }
     }
 
+    pub fn is_quote_mode(&self) -> bool {
+        if let Self::Initialized { mode, .. } = self {
+            matches!(mode, Mode::Quote)
+        } else {
+            false
+        }
+    }
+
     pub fn is_help_mode(&self) -> bool {
         if let Self::Initialized { mode, .. } = self {
             matches!(mode, Mode::Help)
@@ -444,6 +2036,30 @@ impl AppState {
         }
     }
 
+    pub fn is_accounts_mode(&self) -> bool {
+        if let Self::Initialized { mode, .. } = self {
+            matches!(mode, Mode::Accounts)
+        } else {
+            false
+        }
+    }
+
+    pub fn is_links_mode(&self) -> bool {
+        if let Self::Initialized { mode, .. } = self {
+            matches!(mode, Mode::Links)
+        } else {
+            false
+        }
+    }
+
+    pub fn is_messages_mode(&self) -> bool {
+        if let Self::Initialized { mode, .. } = self {
+            matches!(mode, Mode::Messages)
+        } else {
+            false
+        }
+    }
+
     pub fn get_tl_list_state(&self) -> ListState {
         if let Self::Initialized { tl_list_state, .. } = self {
             tl_list_state.clone()
@@ -517,191 +2133,60 @@ impl AppState {
         }
     }
 
-    pub fn get_tl_current_cursor_index(&self) -> usize {
+    pub fn get_current_notification(&self) -> Option<Notification> {
         if let Self::Initialized {
-            tl_current_cursor_index,
-            ..
-        } = self
-        {
-            *tl_current_cursor_index
-        } else {
-            0
-        }
-    }
-
-    pub fn set_tl_current_cursor_index(&mut self, index: usize) {
-        if let Self::Initialized {
-            tl_current_cursor_index,
-            ..
-        } = self
-        {
-            *tl_current_cursor_index = index;
-        }
-    }
-
-    pub fn get_cursors(&self) -> Vec<Option<String>> {
-        if let Self::Initialized { cursors, .. } = self {
-            cursors.clone()
-        } else {
-            Vec::new()
-        }
-    }
-
-    pub fn set_cursors(&mut self, cursors: Vec<Option<String>>) {
-        if let Self::Initialized { cursors: c, .. } = self {
-            *c = cursors;
-        }
-    }
-
-    pub fn get_current_cursor(&self) -> Option<String> {
-        if let Self::Initialized {
-            tl_current_cursor_index,
-            cursors,
+            notifications,
+            notifications_list_position,
             ..
         } = self
         {
-            cursors.get(*tl_current_cursor_index).cloned().unwrap()
+            notifications
+                .as_ref()
+                .and_then(|n| n.get(*notifications_list_position).cloned())
         } else {
             None
         }
     }
 
-    pub fn get_next_cursor(&self) -> Option<String> {
-        if let Self::Initialized {
-            tl_current_cursor_index,
-            cursors,
-            ..
-        } = self
-        {
-            if *tl_current_cursor_index + 1 == cursors.len() {
-                return None;
-            }
-            cursors.get(*tl_current_cursor_index + 1).cloned().unwrap()
+    pub fn get_timeline_pages(&self) -> Pages {
+        if let Self::Initialized { timeline_pages, .. } = self {
+            timeline_pages.clone()
         } else {
-            None
+            Pages::default()
         }
     }
 
-    pub fn get_prev_cursor(&self) -> Option<String> {
-        if let Self::Initialized {
-            tl_current_cursor_index,
-            cursors,
-            ..
-        } = self
-        {
-            if *tl_current_cursor_index == 0 {
-                return None;
-            }
-            cursors.get(*tl_current_cursor_index - 1).cloned().unwrap()
-        } else {
-            None
+    pub fn set_timeline_pages(&mut self, pages: Pages) {
+        if let Self::Initialized { timeline_pages, .. } = self {
+            *timeline_pages = pages;
         }
     }
 
     pub fn set_loading(&mut self, is_loading: bool) {
-        if let Self::Initialized { is_loading: l, .. } = self {
+        if let Self::Initialized { is_loading: l, .. } | Self::Login { is_loading: l, .. } = self {
             *l = is_loading;
         }
     }
 
     pub fn is_loading(&self) -> bool {
-        if let Self::Initialized { is_loading, .. } = self {
+        if let Self::Initialized { is_loading, .. } | Self::Login { is_loading, .. } = self {
             *is_loading
         } else {
             false
         }
     }
 
-    pub fn get_search_current_cursor_index(&self) -> usize {
-        if let Self::Initialized {
-            search_current_cursor_index,
-            ..
-        } = self
-        {
-            *search_current_cursor_index
-        } else {
-            0
-        }
-    }
-
-    pub fn set_search_current_cursor_index(&mut self, index: usize) {
-        if let Self::Initialized {
-            search_current_cursor_index,
-            ..
-        } = self
-        {
-            *search_current_cursor_index = index;
-        }
-    }
-
-    pub fn get_search_cursors(&self) -> Vec<Option<String>> {
-        if let Self::Initialized { search_cursors, .. } = self {
-            search_cursors.clone()
-        } else {
-            Vec::new()
-        }
-    }
-
-    pub fn set_search_cursors(&mut self, cursors: Vec<Option<String>>) {
-        if let Self::Initialized {
-            search_cursors: c, ..
-        } = self
-        {
-            *c = cursors;
-        }
-    }
-
-    pub fn get_search_current_cursor(&self) -> Option<String> {
-        if let Self::Initialized {
-            search_current_cursor_index,
-            search_cursors,
-            ..
-        } = self
-        {
-            search_cursors
-                .get(*search_current_cursor_index)
-                .cloned()
-                .unwrap()
-        } else {
-            None
-        }
-    }
-
-    pub fn get_search_next_cursor(&self) -> Option<String> {
-        if let Self::Initialized {
-            search_current_cursor_index,
-            search_cursors,
-            ..
-        } = self
-        {
-            if *search_current_cursor_index + 1 == search_cursors.len() {
-                return None;
-            }
-            search_cursors
-                .get(*search_current_cursor_index + 1)
-                .cloned()
-                .unwrap()
+    pub fn get_search_pages(&self) -> Pages {
+        if let Self::Initialized { search_pages, .. } = self {
+            search_pages.clone()
         } else {
-            None
+            Pages::default()
         }
     }
 
-    pub fn get_search_prev_cursor(&self) -> Option<String> {
-        if let Self::Initialized {
-            search_current_cursor_index,
-            search_cursors,
-            ..
-        } = self
-        {
-            if *search_current_cursor_index == 0 {
-                return None;
-            }
-            search_cursors
-                .get(*search_current_cursor_index - 1)
-                .cloned()
-                .unwrap()
-        } else {
-            None
+    pub fn set_search_pages(&mut self, pages: Pages) {
+        if let Self::Initialized { search_pages, .. } = self {
+            *search_pages = pages;
         }
     }
 
@@ -720,8 +2205,19 @@ impl AppState {
     }
 }
 
-impl Default for AppState {
-    fn default() -> Self {
-        Self::Init
-    }
+/// Our relationship to an account, created empty if it was not loaded.
+fn viewer_mut(viewer: &mut Option<ViewerState>) -> &mut ViewerStateData {
+    viewer.get_or_insert_with(|| {
+        ViewerStateData {
+            blocked_by: None,
+            blocking: None,
+            blocking_by_list: None,
+            followed_by: None,
+            following: None,
+            known_followers: None,
+            muted: None,
+            muted_by_list: None,
+        }
+        .into()
+    })
 }
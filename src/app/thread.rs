@@ -0,0 +1,158 @@
+use atrium_api::{
+    app::bsky::feed::{
+        defs::{PostViewData, ThreadViewPost, ThreadViewPostParentRefs, ThreadViewPostRepliesItem},
+        get_post_thread::OutputThreadRefs,
+    },
+    types::{string::Did, Union},
+};
+use ratatui::widgets::ListState;
+
+/// A post in a thread, or a placeholder for one that cannot be shown.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+pub enum ThreadPost {
+    Post(PostViewData),
+    NotFound,
+    Blocked,
+}
+
+#[derive(Clone)]
+pub struct ThreadItem {
+    pub post: ThreadPost,
+    /// 0 for the parents and the post the thread was opened for, 1 for its direct replies, ...
+    pub depth: usize,
+    /// Whether this is the post the thread was opened for.
+    pub is_anchor: bool,
+}
+
+/// A thread flattened into display order: the parents from the root down, the anchor post, and
+/// then its replies depth first.
+#[derive(Clone)]
+pub struct Thread {
+    pub uri: String,
+    pub items: Vec<ThreadItem>,
+    pub list_state: ListState,
+    pub list_position: usize,
+}
+
+impl Thread {
+    pub fn new(uri: String, thread: Union<OutputThreadRefs>) -> Self {
+        let mut items = Vec::new();
+        match thread {
+            Union::Refs(OutputThreadRefs::AppBskyFeedDefsThreadViewPost(view)) => {
+                push_parents(&mut items, view.parent.clone());
+                items.push(thread_item(
+                    ThreadPost::Post(view.post.data.clone()),
+                    0,
+                    true,
+                ));
+                push_replies(&mut items, &view, 1);
+            }
+            Union::Refs(OutputThreadRefs::AppBskyFeedDefsNotFoundPost(_)) => {
+                items.push(thread_item(ThreadPost::NotFound, 0, true))
+            }
+            Union::Refs(OutputThreadRefs::AppBskyFeedDefsBlockedPost(_)) => {
+                items.push(thread_item(ThreadPost::Blocked, 0, true))
+            }
+            Union::Unknown(_) => {}
+        }
+
+        let list_position = items.iter().position(|item| item.is_anchor).unwrap_or(0);
+        Self {
+            uri,
+            items,
+            list_state: ListState::default().with_selected(Some(list_position)),
+            list_position,
+        }
+    }
+
+    /// Replaces the posts with a freshly loaded version of the same thread, keeping the selection.
+    pub fn reload(&mut self, thread: Union<OutputThreadRefs>) {
+        let reloaded = Self::new(self.uri.clone(), thread);
+        self.items = reloaded.items;
+        self.list_position = self.list_position.min(self.items.len().saturating_sub(1));
+        self.list_state.select(Some(self.list_position));
+    }
+
+    pub fn current_post(&self) -> Option<PostViewData> {
+        match self.items.get(self.list_position).map(|item| &item.post) {
+            Some(ThreadPost::Post(post)) => Some(post.clone()),
+            _ => None,
+        }
+    }
+
+    /// Shows the post with `uri` as not found, as it is once deleted.
+    pub fn remove_post(&mut self, uri: &str) {
+        for item in &mut self.items {
+            if matches!(&item.post, ThreadPost::Post(post) if post.uri == uri) {
+                item.post = ThreadPost::NotFound;
+            }
+        }
+    }
+
+    /// Shows the posts of `did` as blocked, as when it is muted or blocked.
+    pub fn remove_author(&mut self, did: &Did) {
+        for item in &mut self.items {
+            if matches!(&item.post, ThreadPost::Post(post) if &post.author.did == did) {
+                item.post = ThreadPost::Blocked;
+            }
+        }
+    }
+
+    pub fn move_scroll_up(&mut self) {
+        if self.list_position > 0 {
+            self.list_position -= 1;
+            self.list_state.select(Some(self.list_position));
+        }
+    }
+
+    pub fn move_scroll_down(&mut self) {
+        if self.list_position + 1 < self.items.len() {
+            self.list_position += 1;
+            self.list_state.select(Some(self.list_position));
+        }
+    }
+}
+
+fn thread_item(post: ThreadPost, depth: usize, is_anchor: bool) -> ThreadItem {
+    ThreadItem {
+        post,
+        depth,
+        is_anchor,
+    }
+}
+
+fn push_parents(items: &mut Vec<ThreadItem>, parent: Option<Union<ThreadViewPostParentRefs>>) {
+    let item = match parent {
+        Some(Union::Refs(ThreadViewPostParentRefs::ThreadViewPost(view))) => {
+            push_parents(items, view.parent.clone());
+            ThreadPost::Post(view.post.data.clone())
+        }
+        Some(Union::Refs(ThreadViewPostParentRefs::NotFoundPost(_))) => ThreadPost::NotFound,
+        Some(Union::Refs(ThreadViewPostParentRefs::BlockedPost(_))) => ThreadPost::Blocked,
+        _ => return,
+    };
+    items.push(thread_item(item, 0, false));
+}
+
+fn push_replies(items: &mut Vec<ThreadItem>, view: &ThreadViewPost, depth: usize) {
+    for reply in view.replies.iter().flatten() {
+        match reply {
+            Union::Refs(ThreadViewPostRepliesItem::ThreadViewPost(reply)) => {
+                items.push(thread_item(
+                    ThreadPost::Post(reply.post.data.clone()),
+                    depth,
+                    false,
+                ));
+                push_replies(items, reply, depth + 1);
+            }
+            Union::Refs(ThreadViewPostRepliesItem::NotFoundPost(_)) => {
+                items.push(thread_item(ThreadPost::NotFound, depth, false))
+            }
+            Union::Refs(ThreadViewPostRepliesItem::BlockedPost(_)) => {
+                items.push(thread_item(ThreadPost::Blocked, depth, false))
+            }
+            Union::Unknown(_) => {}
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use atrium_api::{
+    app::bsky::{
+        embed::{external, images, record, record_with_media, video},
+        feed::{defs::PostViewEmbedRefs, post},
+    },
+    types::{TryFromUnknown, Union},
+};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+/// How many levels of quoted posts inside quoted posts are shown.
+const MAX_QUOTE_DEPTH: usize = 2;
+
+/// Renders the images, link card, video or quoted post attached to a post.
+pub fn lines<'a>(embed: &Union<PostViewEmbedRefs>) -> Vec<Line<'a>> {
+    match embed {
+        Union::Refs(PostViewEmbedRefs::AppBskyEmbedImagesView(view)) => images(view),
+        Union::Refs(PostViewEmbedRefs::AppBskyEmbedVideoView(view)) => video(view),
+        Union::Refs(PostViewEmbedRefs::AppBskyEmbedExternalView(view)) => external(view),
+        Union::Refs(PostViewEmbedRefs::AppBskyEmbedRecordView(view)) => record(view, 0),
+        Union::Refs(PostViewEmbedRefs::AppBskyEmbedRecordWithMediaView(view)) => {
+            record_with_media(view, 0)
+        }
+        Union::Unknown(_) => vec![],
+    }
+}
+
+fn images<'a>(view: &images::View) -> Vec<Line<'a>> {
+    let count = view.images.len();
+    let mut lines = vec![Line::from(Span::styled(
+        format!("🖼 {} image{}", count, if count == 1 { "" } else { "s" }),
+        Style::default().fg(Color::Yellow),
+    ))];
+    for (i, image) in view.images.iter().enumerate() {
+        lines.push(alt_text(format!("  [{}] ", i + 1), &image.alt));
+    }
+    lines
+}
+
+fn video<'a>(view: &video::View) -> Vec<Line<'a>> {
+    let aspect_ratio = view
+        .aspect_ratio
+        .as_ref()
+        .map(|ratio| format!(" {}:{}", ratio.width, ratio.height))
+        .unwrap_or_default();
+    vec![
+        Line::from(Span::styled(
+            format!("▶ Video{aspect_ratio} (duration unknown)"),
+            Style::default().fg(Color::Yellow),
+        )),
+        alt_text("  ".to_string(), view.alt.as_deref().unwrap_or_default()),
+    ]
+}
+
+fn external<'a>(view: &external::View) -> Vec<Line<'a>> {
+    let mut lines = vec![Line::from(Span::styled(
+        format!("🔗 {}", view.external.title),
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    if let Some(description) = view.external.description.lines().next() {
+        lines.push(Line::from(Span::styled(
+            format!("   {description}"),
+            Style::default().fg(Color::Gray),
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        format!("   {}", view.external.uri),
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::UNDERLINED),
+    )));
+    lines
+}
+
+fn record_with_media<'a>(view: &record_with_media::View, depth: usize) -> Vec<Line<'a>> {
+    let mut lines = match &view.media {
+        Union::Refs(record_with_media::ViewMediaRefs::AppBskyEmbedImagesView(view)) => images(view),
+        Union::Refs(record_with_media::ViewMediaRefs::AppBskyEmbedVideoView(view)) => video(view),
+        Union::Refs(record_with_media::ViewMediaRefs::AppBskyEmbedExternalView(view)) => {
+            external(view)
+        }
+        Union::Unknown(_) => vec![],
+    };
+    lines.extend(record(&view.record, depth));
+    lines
+}
+
+fn record<'a>(view: &record::View, depth: usize) -> Vec<Line<'a>> {
+    let notice = |text: &str| {
+        vec![quoted(Line::from(Span::styled(
+            text.to_string(),
+            Style::default().fg(Color::Gray),
+        )))]
+    };
+
+    match &view.record {
+        Union::Refs(record::ViewRecordRefs::ViewRecord(quote)) => {
+            let display_name = quote.author.display_name.clone().unwrap_or_default();
+            let mut lines = vec![Line::from(vec![
+                Span::styled(
+                    format!("{display_name} "),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(
+                    format!("@{}", quote.author.handle.as_str()),
+                    Style::default().fg(Color::Gray),
+                ),
+            ])];
+            if let Ok(r) = post::Record::try_from_unknown(quote.value.clone()) {
+                lines.extend(r.text.lines().map(|line| Line::from(line.to_string())));
+            }
+            for embed in quote.embeds.iter().flatten() {
+                lines.extend(quoted_embed(embed, depth + 1));
+            }
+            lines.into_iter().map(quoted).collect()
+        }
+        Union::Refs(record::ViewRecordRefs::ViewNotFound(_)) => notice("Quoted post not found"),
+        Union::Refs(record::ViewRecordRefs::ViewBlocked(_)) => {
+            notice("Quoted post is unavailable because of a block")
+        }
+        Union::Refs(record::ViewRecordRefs::ViewDetached(_)) => {
+            notice("Quoted post was removed by its author")
+        }
+        Union::Refs(record::ViewRecordRefs::AppBskyFeedDefsGeneratorView(feed)) => {
+            notice(&format!("Feed: {}", feed.display_name))
+        }
+        Union::Refs(record::ViewRecordRefs::AppBskyGraphDefsListView(list)) => {
+            notice(&format!("List: {}", list.name))
+        }
+        Union::Refs(record::ViewRecordRefs::AppBskyLabelerDefsLabelerView(labeler)) => {
+            notice(&format!("Labeler: @{}", labeler.creator.handle.as_str()))
+        }
+        Union::Refs(record::ViewRecordRefs::AppBskyGraphDefsStarterPackViewBasic(pack)) => notice(
+            &format!("Starter pack by @{}", pack.creator.handle.as_str()),
+        ),
+        Union::Unknown(_) => vec![],
+    }
+}
+
+fn quoted_embed<'a>(embed: &Union<record::ViewRecordEmbedsItem>, depth: usize) -> Vec<Line<'a>> {
+    match embed {
+        Union::Refs(record::ViewRecordEmbedsItem::AppBskyEmbedImagesView(view)) => images(view),
+        Union::Refs(record::ViewRecordEmbedsItem::AppBskyEmbedVideoView(view)) => video(view),
+        Union::Refs(record::ViewRecordEmbedsItem::AppBskyEmbedExternalView(view)) => external(view),
+        Union::Refs(record::ViewRecordEmbedsItem::AppBskyEmbedRecordView(_))
+        | Union::Refs(record::ViewRecordEmbedsItem::AppBskyEmbedRecordWithMediaView(_))
+            if depth >= MAX_QUOTE_DEPTH =>
+        {
+            vec![Line::from(Span::styled(
+                "❝ Quoted post",
+                Style::default().fg(Color::Gray),
+            ))]
+        }
+        Union::Refs(record::ViewRecordEmbedsItem::AppBskyEmbedRecordView(view)) => {
+            record(view, depth)
+        }
+        Union::Refs(record::ViewRecordEmbedsItem::AppBskyEmbedRecordWithMediaView(view)) => {
+            record_with_media(view, depth)
+        }
+        Union::Unknown(_) => vec![],
+    }
+}
+
+fn alt_text<'a>(prefix: String, alt: &str) -> Line<'a> {
+    if alt.is_empty() {
+        Line::from(Span::styled(
+            format!("{prefix}(no alt text)"),
+            Style::default().fg(Color::DarkGray),
+        ))
+    } else {
+        Line::from(Span::styled(
+            format!("{prefix}{}", alt.replace('\n', " ")),
+            Style::default().fg(Color::Gray),
+        ))
+    }
+}
+
+/// Indents a line of a quoted post with a bar.
+fn quoted(line: Line<'_>) -> Line<'_> {
+    let mut spans = vec![Span::styled("▎ ", Style::default().fg(Color::DarkGray))];
+    spans.extend(line.spans);
+    Line::from(spans)
+}
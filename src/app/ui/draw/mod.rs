@@ -1,17 +1,42 @@
-use atrium_api::app::bsky::feed::{post, repost};
+mod embed;
+
+use atrium_api::{
+    app::bsky::{
+        feed::{
+            defs::{FeedViewPostReasonRefs, PostViewData},
+            post, repost,
+        },
+        richtext::facet,
+    },
+    types::Union,
+};
 use bsky_sdk::api::types::TryFromUnknown;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, Utc};
+use image::{imageops, Rgb, RgbImage};
 use ratatui::{
-    layout::{Alignment, Constraint},
+    layout::{Alignment, Constraint, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{
         Block, BorderType, Borders, Cell, List, ListItem, Padding, Paragraph, Row, Table, Tabs,
+        Wrap,
     },
 };
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
-    app::state::{AppState, Tab},
+    app::{
+        attachment::{AttachmentPrompt, MAX_ATTACHMENTS},
+        composer::{Wrapped, MAX_GRAPHEMES},
+        drafts::DraftKind,
+        follows::{FollowList, FollowListKind},
+        message::Message,
+        muted_words::{self, MutedWordPrompt, TARGET_CONTENT},
+        preview::{self, PreviewImage, Thumbnail},
+        profile::Profile,
+        state::{AppState, LoginField, Moderation, Tab},
+        thread::ThreadPost,
+    },
     bsky, utils,
 };
 
@@ -96,7 +121,7 @@ pub fn help<'a>() -> Table<'a> {
         ]),
         Row::new(vec![
             Cell::from(""),
-            Cell::from("Home/Notifications/Search"),
+            Cell::from("Any view"),
             Cell::from("?"),
             Cell::from("Show help popup"),
         ]),
@@ -106,12 +131,42 @@ pub fn help<'a>() -> Table<'a> {
             Cell::from("/"),
             Cell::from("Search mode"),
         ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Any view"),
+            Cell::from("a"),
+            Cell::from("Switch account popup"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Any view"),
+            Cell::from("m"),
+            Cell::from("Message log popup"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Any view"),
+            Cell::from("x"),
+            Cell::from("Dismiss status message"),
+        ]),
         Row::new(vec![
             Cell::from(""),
             Cell::from("Home/Notifications"),
             Cell::from("n"),
             Cell::from("New post popup"),
         ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Home"),
+            Cell::from("T"),
+            Cell::from("New thread popup (long text is split into a chain of posts)"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Home"),
+            Cell::from("D"),
+            Cell::from("Drafts popup"),
+        ]),
         Row::new(vec![
             Cell::from(""),
             Cell::from("Home/Notifications"),
@@ -146,8 +201,20 @@ pub fn help<'a>() -> Table<'a> {
             Cell::from(""),
             Cell::from("Home/Search"),
             Cell::from("Enter"),
+            Cell::from("Open thread of selected post"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Home/Search"),
+            Cell::from("o"),
             Cell::from("Selected post open in browser"),
         ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Home/Search"),
+            Cell::from("L"),
+            Cell::from("Links in selected post popup"),
+        ]),
         Row::new(vec![
             Cell::from(""),
             Cell::from("Home/Search"),
@@ -160,22 +227,100 @@ pub fn help<'a>() -> Table<'a> {
             Cell::from("Ctrl+l"),
             Cell::from("Like selected post (unlike if already liked)"),
         ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Home/Search"),
+            Cell::from("Q"),
+            Cell::from("Quote selected post popup"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Home/Search"),
+            Cell::from("d"),
+            Cell::from("Delete selected post (only your own, after confirming)"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Home/Search"),
+            Cell::from("p"),
+            Cell::from("Profile of the selected post's author"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Notifications"),
+            Cell::from("p"),
+            Cell::from("Profile of the selected notification's author"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Home/Notifications/Search"),
+            Cell::from("@"),
+            Cell::from("Go to the profile of a handle"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Home/Notifications/Search"),
+            Cell::from("F"),
+            Cell::from("Follow/unfollow the author of the selected post or notification"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Home/Notifications/Search"),
+            Cell::from("M, B"),
+            Cell::from("Mute/block the author (or unmute/unblock)"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Home/Notifications/Search"),
+            Cell::from("U, K, W"),
+            Cell::from("Muted accounts, blocked accounts, muted words"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from("Home/Notifications/Search"),
+            Cell::from("Enter"),
+            Cell::from("Show a post hidden by a muted word"),
+        ]),
         // Post mode
         Row::new(vec![
-            Cell::from("Post/Reply/Search"),
+            Cell::from("Post/Reply/Quote/Search"),
             Cell::from(""),
             Cell::from("Esc"),
-            Cell::from("Return to normal mode"),
+            Cell::from("Return to normal mode (unsent text is kept as a draft)"),
         ]),
         Row::new(vec![
             Cell::from(""),
             Cell::from(""),
             Cell::from("Enter"),
-            Cell::from("Send post/reply/search"),
+            Cell::from("Send post/reply/quote/search"),
+        ]),
+        Row::new(vec![
+            Cell::from("Post/Reply/Quote"),
+            Cell::from(""),
+            Cell::from("Ctrl+s, Ctrl+Enter"),
+            Cell::from("Send post/reply/quote"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Shift+Enter, Alt+Enter"),
+            Cell::from("Insert a line break"),
         ]),
         Row::new(vec![
             Cell::from(""),
             Cell::from(""),
+            Cell::from("Up/Down, Ctrl+p/Ctrl+n"),
+            Cell::from("Move cursor to the previous/next line"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Delete, Ctrl+d"),
+            Cell::from("Delete next character"),
+        ]),
+        Row::new(vec![
+            Cell::from("Post/Reply/Quote/Search"),
+            Cell::from(""),
             Cell::from("Left, Ctrl+b"),
             Cell::from("Move cursor left"),
         ]),
@@ -203,6 +348,74 @@ pub fn help<'a>() -> Table<'a> {
             Cell::from("Backspace, Ctrl+h"),
             Cell::from("Delete word"),
         ]),
+        Row::new(vec![
+            Cell::from("Post/Reply/Quote"),
+            Cell::from(""),
+            Cell::from("Ctrl+o"),
+            Cell::from("Attach an image (or drop a file onto the terminal)"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Ctrl+t"),
+            Cell::from("Edit the alt text of the last image"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Ctrl+x"),
+            Cell::from("Remove the last image"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Ctrl+g"),
+            Cell::from("Write the post in $VISUAL/$EDITOR"),
+        ]),
+        Row::new(vec![
+            Cell::from("New thread"),
+            Cell::from(""),
+            Cell::from("Ctrl+k"),
+            Cell::from("Turn 1/n numbering on or off"),
+        ]),
+        // Drafts mode
+        Row::new(vec![
+            Cell::from("Drafts"),
+            Cell::from(""),
+            Cell::from("j/k, Enter"),
+            Cell::from("Select and open a draft"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("s, d"),
+            Cell::from("Send, delete the selected draft"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Esc, q, D"),
+            Cell::from("Return to normal mode"),
+        ]),
+        // Muted words mode
+        Row::new(vec![
+            Cell::from("Muted words"),
+            Cell::from(""),
+            Cell::from("j/k, a, d"),
+            Cell::from("Select, add, delete a muted word"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Tab, Ctrl+t"),
+            Cell::from("When adding: text and tags or tags only, how long to mute it for"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Esc, q, W"),
+            Cell::from("Return to normal mode"),
+        ]),
         // Help mode
         Row::new(vec![
             Cell::from("Help"),
@@ -210,6 +423,201 @@ pub fn help<'a>() -> Table<'a> {
             Cell::from("Esc, q, ?"),
             Cell::from("Return to normal mode"),
         ]),
+        // Accounts mode
+        Row::new(vec![
+            Cell::from("Accounts"),
+            Cell::from(""),
+            Cell::from("j/k, Enter"),
+            Cell::from("Select and switch to an account"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Esc, q, a"),
+            Cell::from("Return to normal mode"),
+        ]),
+        // Thread view
+        Row::new(vec![
+            Cell::from("Thread"),
+            Cell::from(""),
+            Cell::from("j/k, Enter"),
+            Cell::from("Select a post / open its thread"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("N, Q, Ctrl+l, Ctrl+r, d"),
+            Cell::from("Reply, quote, like, repost, delete selected post"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("o, L, r, p, @"),
+            Cell::from("Open in browser, links, reload, author profile, go to profile"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("F"),
+            Cell::from("Follow/unfollow the author of the selected post"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("M, B, U, K, W"),
+            Cell::from("Mute/block the account, muted/blocked accounts, muted words"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Esc, q, h"),
+            Cell::from("Back to previous thread or tab"),
+        ]),
+        // Profile view
+        Row::new(vec![
+            Cell::from("Profile"),
+            Cell::from(""),
+            Cell::from("j/k, h/l, Enter"),
+            Cell::from("Select a post, previous/next page, open its thread"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("N, Q, Ctrl+l, Ctrl+r, d"),
+            Cell::from("Reply, quote, like, repost, delete selected post"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("o, L, r, p, @"),
+            Cell::from("Open in browser, links, reload, author profile, go to profile"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("F"),
+            Cell::from("Follow/unfollow the account"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("M, B, U, K, W"),
+            Cell::from("Mute/block the account, muted/blocked accounts, muted words"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("f, g"),
+            Cell::from("Followers, following of the account"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Esc, q, Backspace"),
+            Cell::from("Back to previous view or tab"),
+        ]),
+        // Follow lists
+        Row::new(vec![
+            Cell::from("Followers/Following"),
+            Cell::from(""),
+            Cell::from("j/k, h/l"),
+            Cell::from("Select an account, previous/next page"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Enter, p, F, r"),
+            Cell::from("Open profile, follow/unfollow, reload"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("M, B, U, K, W"),
+            Cell::from("Mute/block the account, muted/blocked accounts, muted words"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("/"),
+            Cell::from("Filter the loaded accounts (Enter: done, Esc: clear)"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Esc, q, Backspace"),
+            Cell::from("Back to previous view or tab"),
+        ]),
+        // Delete post confirmation
+        Row::new(vec![
+            Cell::from("Delete post"),
+            Cell::from(""),
+            Cell::from("y, Enter"),
+            Cell::from("Delete the post"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("n, Esc, q"),
+            Cell::from("Keep the post"),
+        ]),
+        // Mute/block confirmation
+        Row::new(vec![
+            Cell::from("Mute/block"),
+            Cell::from(""),
+            Cell::from("y, Enter"),
+            Cell::from("Mute, unmute, block or unblock the account"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("n, Esc, q"),
+            Cell::from("Cancel"),
+        ]),
+        // Links mode
+        Row::new(vec![
+            Cell::from("Links"),
+            Cell::from(""),
+            Cell::from("j/k, Enter"),
+            Cell::from("Select and open a link, mention or tag"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Esc, q, L"),
+            Cell::from("Return to normal mode"),
+        ]),
+        // Messages mode
+        Row::new(vec![
+            Cell::from("Messages"),
+            Cell::from(""),
+            Cell::from("j/k"),
+            Cell::from("Scroll through past messages"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Esc, q, m"),
+            Cell::from("Return to normal mode"),
+        ]),
+        // Login screen
+        Row::new(vec![
+            Cell::from("Login"),
+            Cell::from(""),
+            Cell::from("Tab, Down / Up"),
+            Cell::from("Next / previous field"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Enter"),
+            Cell::from("Log in / retry"),
+        ]),
+        Row::new(vec![
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from("Esc"),
+            Cell::from("Back to the previous account, or quit"),
+        ]),
     ];
 
     Table::new(
@@ -241,61 +649,10 @@ pub fn timeline<'a>(state: &AppState) -> List<'a> {
             .iter()
             .map(|feed| {
                 let post = &feed.post;
-                let (text, created_at) =
-                    if let Ok(r) = post::Record::try_from_unknown(post.record.clone()) {
-                        // let c = r.created_at.rsplit('.').last().unwrap();
-                        let c = &r.created_at;
-                        (r.text.clone(), format!("{:?}+0000", c))
-                    } else {
-                        ("".into(), "".into())
-                    };
-                let display_name = post
-                    .author
-                    .display_name
-                    .clone()
-                    .unwrap_or_else(|| "".into());
-                let handle = post.author.handle.to_string();
-                let reply_count = post.reply_count.unwrap_or(0);
-                let repost_count = post.repost_count.unwrap_or(0);
-                let like_count = post.like_count.unwrap_or(0);
-                let duration_text =
-                    match DateTime::parse_from_str(&created_at, "%Y-%m-%dT%H:%M:%S%z") {
-                        Ok(dt) => utils::get_duration_string(dt, Utc::now().fixed_offset()),
-                        Err(_) => "".into(),
-                    };
-                let item = vec![
-                    Line::from(vec![
-                        Span::styled(
-                            format!("{display_name} "),
-                            Style::default().fg(Color::White),
-                        ),
-                        Span::styled(
-                            format!("@{handle} {duration_text}"),
-                            Style::default().fg(Color::Gray),
-                        ),
-                    ]),
-                    Line::from(text),
-                    Line::from(vec![
-                        Span::styled(
-                            format!("↩ {}", reply_count),
-                            Style::default().fg(Color::Gray),
-                        ),
-                        Span::styled(
-                            format!("   🔁 {}", repost_count),
-                            Style::default().fg(Color::Green),
-                        ),
-                        Span::styled(
-                            format!("   ❤ {}", like_count),
-                            Style::default().fg(Color::Red),
-                        ),
-                    ]),
-                    Line::from(Span::styled(
-                        border.clone(),
-                        Style::default().fg(Color::Gray),
-                    )),
-                ];
-
-                ListItem::new(item)
+                if let Some(word) = state.get_hidden_word(&post.uri) {
+                    return hidden_item(word, &border);
+                }
+                ListItem::new(post_lines(post, &border, false))
             })
             .collect(),
         None => vec![],
@@ -310,7 +667,7 @@ pub fn timeline<'a>(state: &AppState) -> List<'a> {
                 .padding(Padding::new(1, 1, 1, 1))
                 .title(format!(
                     "Home ({}: {})",
-                    state.get_tl_current_cursor_index() + 1,
+                    state.get_timeline_pages().number(),
                     state.get_timeline().unwrap_or_default().len()
                 ))
                 .border_type(BorderType::Plain),
@@ -326,61 +683,10 @@ pub fn search_results<'a>(state: &AppState) -> List<'a> {
         Some(feeds) => feeds
             .iter()
             .map(|feed| {
-                let post = &feed;
-                let (text, created_at) =
-                    if let Ok(r) = post::Record::try_from_unknown(post.record.clone()) {
-                        let c = &r.created_at;
-                        (r.text.clone(), format!("{:?}+0000", c))
-                    } else {
-                        ("".into(), "".into())
-                    };
-                let display_name = post
-                    .author
-                    .display_name
-                    .clone()
-                    .unwrap_or_else(|| "".into());
-                let handle = post.author.handle.to_string();
-                let reply_count = post.reply_count.unwrap_or(0);
-                let repost_count = post.repost_count.unwrap_or(0);
-                let like_count = post.like_count.unwrap_or(0);
-                let duration_text =
-                    match DateTime::parse_from_str(&created_at, "%Y-%m-%dT%H:%M:%S%z") {
-                        Ok(dt) => utils::get_duration_string(dt, Utc::now().fixed_offset()),
-                        Err(_) => "".into(),
-                    };
-                let item = vec![
-                    Line::from(vec![
-                        Span::styled(
-                            format!("{display_name} "),
-                            Style::default().fg(Color::White),
-                        ),
-                        Span::styled(
-                            format!("@{handle} {duration_text}"),
-                            Style::default().fg(Color::Gray),
-                        ),
-                    ]),
-                    Line::from(text),
-                    Line::from(vec![
-                        Span::styled(
-                            format!("↩ {}", reply_count),
-                            Style::default().fg(Color::Gray),
-                        ),
-                        Span::styled(
-                            format!("   🔁 {}", repost_count),
-                            Style::default().fg(Color::Green),
-                        ),
-                        Span::styled(
-                            format!("   ❤ {}", like_count),
-                            Style::default().fg(Color::Red),
-                        ),
-                    ]),
-                    Line::from(Span::styled(
-                        border.clone(),
-                        Style::default().fg(Color::Gray),
-                    )),
-                ];
-
-                ListItem::new(item)
+                if let Some(word) = state.get_hidden_word(&feed.uri) {
+                    return hidden_item(word, &border);
+                }
+                ListItem::new(post_lines(feed, &border, false))
             })
             .collect(),
         None => vec![],
@@ -395,13 +701,300 @@ pub fn search_results<'a>(state: &AppState) -> List<'a> {
                 .padding(Padding::new(1, 1, 1, 1))
                 .title(format!(
                     "Search Results ({}: {})",
-                    state.get_search_current_cursor_index() + 1,
+                    state.get_search_pages().number(),
                     state.get_search_results().unwrap_or_default().len()
                 ))
                 .border_type(BorderType::Plain),
         )
 }
 
+pub fn thread<'a>(state: &AppState) -> List<'a> {
+    let thread = match state.get_thread() {
+        Some(thread) => thread,
+        None => return List::new(Vec::<ListItem>::new()),
+    };
+    let size = crossterm::terminal::size().unwrap();
+
+    let list_items: Vec<ListItem> = thread
+        .items
+        .iter()
+        .map(|item| {
+            let indent = "│ ".repeat(item.depth);
+            let indent_width = indent.chars().count();
+            let border = "=".repeat((size.0 as usize).saturating_sub(4 + indent_width));
+            let indented = |line: Line<'a>| {
+                let mut spans = vec![Span::styled(
+                    indent.clone(),
+                    Style::default().fg(Color::Gray),
+                )];
+                spans.extend(line.spans);
+                Line::from(spans)
+            };
+
+            let post = match &item.post {
+                ThreadPost::Post(post) => post,
+                ThreadPost::NotFound | ThreadPost::Blocked => {
+                    let text = if matches!(item.post, ThreadPost::NotFound) {
+                        "Post not found"
+                    } else {
+                        "Blocked post"
+                    };
+                    return ListItem::new(vec![
+                        indented(Line::from(Span::styled(
+                            text,
+                            Style::default().fg(Color::Gray),
+                        ))),
+                        indented(Line::from(Span::styled(
+                            border,
+                            Style::default().fg(Color::Gray),
+                        ))),
+                    ]);
+                }
+            };
+            let lines = post_lines(post, &border, item.is_anchor);
+            ListItem::new(lines.into_iter().map(indented).collect::<Vec<_>>())
+        })
+        .collect();
+
+    List::new(list_items)
+        .highlight_style(Style::default().bg(Color::Blue))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default())
+                .padding(Padding::new(1, 1, 1, 1))
+                .title(format!(
+                    "Thread ({}/{}, Esc: back)",
+                    thread.list_position + 1,
+                    thread.items.len()
+                ))
+                .border_type(BorderType::Plain),
+        )
+}
+
+pub fn profile<'a>(state: &AppState, profile: &Profile) -> Paragraph<'a> {
+    let profile = &profile.profile;
+    let display_name = profile.display_name.clone().unwrap_or_default();
+    let handle = profile.handle.to_string();
+
+    // What we and the account are to each other.
+    let mut relationship = Vec::new();
+    if let Some(viewer) = &profile.viewer {
+        let badges = [
+            (
+                state.is_following(&profile.did, Some(viewer)),
+                "Following",
+                Color::Blue,
+            ),
+            (viewer.followed_by.is_some(), "Follows you", Color::Cyan),
+            (viewer.muted == Some(true), "Muted", Color::Yellow),
+            (viewer.blocking.is_some(), "Blocked", Color::Red),
+            (viewer.blocked_by == Some(true), "Blocks you", Color::Red),
+        ];
+        for (_, label, color) in badges.iter().filter(|(shown, _, _)| *shown) {
+            relationship.push(Span::styled(
+                format!("[{label}] "),
+                Style::default().fg(*color),
+            ));
+        }
+    }
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                format!("{display_name} "),
+                Style::default()
+                    .fg(Color::LightCyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!("@{handle}"), Style::default().fg(Color::Gray)),
+        ]),
+        Line::from(vec![
+            Span::styled(
+                format!("{}", profile.posts_count.unwrap_or(0)),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" posts   ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("{}", profile.followers_count.unwrap_or(0)),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" followers   ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("{}", profile.follows_count.unwrap_or(0)),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" following", Style::default().fg(Color::Gray)),
+        ]),
+        Line::from(relationship),
+    ];
+    if let Some(description) = profile
+        .description
+        .as_ref()
+        .filter(|d| !d.trim().is_empty())
+    {
+        lines.push(Line::from(""));
+        lines.extend(description.lines().map(|line| Line::from(line.to_string())));
+    }
+
+    Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .title("Profile (f: followers, g: following, Esc: back)")
+            .border_type(BorderType::Plain),
+    )
+}
+
+/// Lines the header of `profile` takes, borders included, up to `max`.
+pub fn profile_height(profile: &Profile, max: u16) -> u16 {
+    let description_lines = profile
+        .profile
+        .description
+        .as_ref()
+        .filter(|d| !d.trim().is_empty())
+        .map_or(0, |d| d.lines().count() + 1);
+    ((3 + description_lines + 2) as u16).min(max)
+}
+
+pub fn author_feed<'a>(profile: &Profile) -> List<'a> {
+    let size = crossterm::terminal::size().unwrap();
+    let border = "=".repeat((size.0 - 4) as usize);
+
+    let list_items: Vec<ListItem> = profile
+        .feed
+        .iter()
+        .map(|feed| {
+            let mut item = Vec::new();
+            if let Some(Union::Refs(FeedViewPostReasonRefs::ReasonRepost(_))) = &feed.reason {
+                item.push(Line::from(Span::styled(
+                    format!("🔁 Reposted by @{}", profile.profile.handle.as_str()),
+                    Style::default().fg(Color::Green),
+                )));
+            }
+            item.extend(post_lines(&feed.post, &border, false));
+            ListItem::new(item)
+        })
+        .collect();
+
+    List::new(list_items)
+        .highlight_style(Style::default().bg(Color::Blue))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default())
+                .padding(Padding::new(1, 1, 1, 1))
+                .title(format!(
+                    "Posts ({}: {})",
+                    profile.pages.number(),
+                    profile.feed.len()
+                ))
+                .border_type(BorderType::Plain),
+        )
+}
+
+pub fn follow_list<'a>(state: &AppState, list: &FollowList) -> List<'a> {
+    let list_items: Vec<ListItem> = list
+        .visible_accounts()
+        .into_iter()
+        .map(|account| {
+            let display_name = account.display_name.clone().unwrap_or_default();
+            let mut header = vec![
+                Span::styled(
+                    format!("{display_name} "),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(
+                    format!("@{} ", account.handle.as_str()),
+                    Style::default().fg(Color::Gray),
+                ),
+            ];
+            if let Some(viewer) = &account.viewer {
+                if state.is_following(&account.did, Some(viewer)) {
+                    header.push(Span::styled(
+                        "[Following] ",
+                        Style::default().fg(Color::Blue),
+                    ));
+                }
+                if viewer.followed_by.is_some() {
+                    header.push(Span::styled(
+                        "[Follows you] ",
+                        Style::default().fg(Color::Cyan),
+                    ));
+                }
+                if viewer.muted == Some(true) {
+                    header.push(Span::styled("[Muted] ", Style::default().fg(Color::Yellow)));
+                }
+                if viewer.blocking.is_some() {
+                    header.push(Span::styled("[Blocked] ", Style::default().fg(Color::Red)));
+                }
+            }
+            let description = account
+                .description
+                .as_deref()
+                .and_then(|description| description.lines().find(|line| !line.trim().is_empty()))
+                .unwrap_or_default()
+                .to_string();
+            ListItem::new(vec![
+                Line::from(header),
+                Line::from(Span::styled(description, Style::default().fg(Color::Gray))),
+            ])
+        })
+        .collect();
+
+    let shown = list_items.len();
+    let count = if shown == list.accounts.len() {
+        shown.to_string()
+    } else {
+        format!("{shown}/{}", list.accounts.len())
+    };
+    let page = list.pages.number();
+    let title = match (&list.subject, list.kind) {
+        (Some(subject), _) => format!(
+            "{} of @{} ({page}: {count}, /: filter, Esc: back)",
+            list.kind,
+            subject.handle.as_str()
+        ),
+        (None, FollowListKind::Blocks) => {
+            format!(
+                "{} ({page}: {count}, B: unblock, /: filter, Esc: back)",
+                list.kind
+            )
+        }
+        (None, _) => {
+            format!(
+                "{} ({page}: {count}, M: unmute, /: filter, Esc: back)",
+                list.kind
+            )
+        }
+    };
+    List::new(list_items)
+        .highlight_style(Style::default().bg(Color::Blue))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default())
+                .padding(Padding::new(1, 1, 1, 1))
+                .title(title)
+                .border_type(BorderType::Plain),
+        )
+}
+
+pub fn follow_filter<'a>(list: &FollowList) -> Paragraph<'a> {
+    let style = if list.is_filtering {
+        Style::default().fg(Color::White)
+    } else {
+        Style::default().fg(Color::Gray)
+    };
+    Paragraph::new(list.filter.value().to_string()).block(
+        Block::default()
+            .style(style)
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .title("Filter (Enter: done, Esc: clear)"),
+    )
+}
+
 pub fn notifications<'a>(state: &AppState) -> List<'a> {
     let notifications = state.get_notifications();
     let my_handle = state.get_handle();
@@ -412,6 +1005,9 @@ pub fn notifications<'a>(state: &AppState) -> List<'a> {
         Some(notifications) => notifications
             .iter()
             .map(|notification| {
+                if let Some(word) = state.get_hidden_word(&notification.uri) {
+                    return hidden_item(word, &border);
+                }
                 let handle = notification.author.handle.to_string();
                 let display_name = notification
                     .author
@@ -470,6 +1066,22 @@ pub fn notifications<'a>(state: &AppState) -> List<'a> {
                     _ => "",
                 };
 
+                let mut reason_line = Line::from(reason_subject);
+                if reason == "follow" {
+                    let following = state.is_following(
+                        &notification.author.did,
+                        notification.author.viewer.as_ref(),
+                    );
+                    reason_line.push_span(Span::styled(
+                        if following {
+                            "  (following)"
+                        } else {
+                            "  (F: follow back)"
+                        },
+                        Style::default().fg(Color::Gray),
+                    ));
+                }
+
                 let item = match subject {
                     Some(subject) => vec![
                         Line::from(vec![
@@ -483,7 +1095,7 @@ pub fn notifications<'a>(state: &AppState) -> List<'a> {
                                 Style::default().fg(Color::Gray),
                             ),
                         ]),
-                        Line::from(reason_subject),
+                        reason_line,
                         Line::from(subject),
                         Line::from(Span::styled(
                             border.clone(),
@@ -502,7 +1114,7 @@ pub fn notifications<'a>(state: &AppState) -> List<'a> {
                                 Style::default().fg(Color::Gray),
                             ),
                         ]),
-                        Line::from(reason_subject),
+                        reason_line,
                         Line::from(Span::styled(
                             border.clone(),
                             Style::default().fg(Color::Gray),
@@ -531,9 +1143,10 @@ pub fn notifications<'a>(state: &AppState) -> List<'a> {
         )
 }
 
-pub fn post_input<'a>(state: &AppState) -> Paragraph<'a> {
-    let text = state.get_input().value().to_string();
-    Paragraph::new(text)
+pub fn post_input<'a>(state: &AppState, text: &Wrapped) -> Paragraph<'a> {
+    let mut lines = composer_lines(text);
+    lines.extend(attachments(state));
+    Paragraph::new(lines)
         .style(Style::default().fg(Color::White).bg(Color::Black))
         .alignment(Alignment::Left)
         .block(
@@ -541,6 +1154,141 @@ pub fn post_input<'a>(state: &AppState) -> Paragraph<'a> {
                 .style(Style::default().fg(Color::White))
                 .borders(Borders::ALL)
                 .title("New post")
+                .title_bottom(grapheme_counter(state))
+                .padding(Padding::new(1, 1, 1, 1)),
+        )
+}
+
+pub fn thread_input<'a>(state: &AppState, text: &Wrapped) -> Paragraph<'a> {
+    let mut lines = composer_lines(text);
+    lines.extend(attachments(state));
+    let numbering = if state.get_number_thread_posts() {
+        "on"
+    } else {
+        "off"
+    };
+    Paragraph::new(lines)
+        .style(Style::default().fg(Color::White).bg(Color::Black))
+        .alignment(Alignment::Left)
+        .block(
+            Block::default()
+                .style(Style::default().fg(Color::White))
+                .borders(Borders::ALL)
+                .title("New thread")
+                .title_bottom(
+                    Line::from(Span::styled(
+                        format!(
+                            " {} graphemes, 1/n numbering {numbering} (Ctrl+k) ",
+                            state.get_composer().graphemes()
+                        ),
+                        Style::default().fg(Color::Gray),
+                    ))
+                    .right_aligned(),
+                )
+                .padding(Padding::new(1, 1, 1, 1)),
+        )
+}
+
+/// The posts the thread being written is split into.
+pub fn thread_preview<'a>(state: &AppState) -> Paragraph<'a> {
+    let posts = state.get_thread_posts();
+    let count = posts.len();
+    let mut lines = Vec::new();
+    for (i, post) in posts.into_iter().enumerate() {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "── {}/{count} ({} graphemes)",
+                i + 1,
+                post.graphemes(true).count()
+            ),
+            Style::default().fg(Color::Cyan),
+        )));
+        lines.extend(post.lines().map(|line| Line::from(line.to_string())));
+        lines.push(Line::from(""));
+    }
+    Paragraph::new(lines)
+        .style(Style::default().fg(Color::White).bg(Color::Black))
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .style(Style::default().fg(Color::White))
+                .borders(Borders::ALL)
+                .title(format!("Preview: {count} posts"))
+                .padding(Padding::new(1, 1, 0, 0)),
+        )
+}
+
+/// The wrapped text of the post being written.
+fn composer_lines<'a>(text: &Wrapped) -> Vec<Line<'a>> {
+    text.lines.iter().cloned().map(Line::from).collect()
+}
+
+/// The number of graphemes written out of the most a post can have, in red once it is over.
+fn grapheme_counter<'a>(state: &AppState) -> Line<'a> {
+    let graphemes = state.get_composer().graphemes();
+    let color = if graphemes > MAX_GRAPHEMES {
+        Color::Red
+    } else if graphemes > MAX_GRAPHEMES * 9 / 10 {
+        Color::Yellow
+    } else {
+        Color::Gray
+    };
+    Line::from(Span::styled(
+        format!(" {graphemes}/{MAX_GRAPHEMES} "),
+        Style::default().fg(color),
+    ))
+    .right_aligned()
+}
+
+/// Lines listing the images attached to the post being written.
+fn attachments<'a>(state: &AppState) -> Vec<Line<'a>> {
+    state
+        .get_attachments()
+        .into_iter()
+        .enumerate()
+        .map(|(i, attachment)| {
+            let alt = if attachment.alt.is_empty() {
+                Span::styled("(no alt text)", Style::default().fg(Color::DarkGray))
+            } else {
+                Span::styled(attachment.alt.clone(), Style::default().fg(Color::Gray))
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("🖼 [{}] {} ", i + 1, attachment.file_name()),
+                    Style::default().fg(Color::Yellow),
+                ),
+                alt,
+            ])
+        })
+        .collect()
+}
+
+pub fn attachment_prompt<'a>(state: &AppState, prompt: &AttachmentPrompt) -> Paragraph<'a> {
+    let title = match prompt {
+        AttachmentPrompt::Path(_) => format!(
+            "Attach image {}/{} (path or drop a file, Esc to cancel)",
+            state.get_attachments().len() + 1,
+            MAX_ATTACHMENTS
+        ),
+        AttachmentPrompt::Alt(_) => {
+            let name = state
+                .get_attachments()
+                .last()
+                .map(|attachment| attachment.file_name())
+                .unwrap_or_default();
+            format!("Alt text for {name}")
+        }
+    };
+
+    Paragraph::new(prompt.input().value().to_string())
+        .style(Style::default().fg(Color::White).bg(Color::Black))
+        .alignment(Alignment::Left)
+        .block(
+            Block::default()
+                .style(Style::default().fg(Color::White))
+                .borders(Borders::ALL)
+                .title(title)
                 .padding(Padding::new(1, 1, 1, 1)),
         )
 }
@@ -559,102 +1307,512 @@ pub fn search_input<'a>(state: &AppState) -> Paragraph<'a> {
         )
 }
 
-pub fn reply_input<'a>(state: &AppState) -> Paragraph<'a> {
+pub fn profile_input<'a>(state: &AppState) -> Paragraph<'a> {
     let text = state.get_input().value().to_string();
+    Paragraph::new(text)
+        .style(Style::default().fg(Color::White).bg(Color::Black))
+        .alignment(Alignment::Left)
+        .block(
+            Block::default()
+                .style(Style::default().fg(Color::White))
+                .borders(Borders::ALL)
+                .title("Go to profile (handle or DID)")
+                .padding(Padding::new(1, 1, 1, 1)),
+        )
+}
 
-    if state.get_tab() == Tab::Search {
-        if let Some(search_result) = state.get_current_search_result() {
-            let display_name = search_result
-                .author
-                .display_name
-                .clone()
-                .unwrap_or_else(|| "".into());
-            let handle = search_result.author.handle.to_string();
-            let parent_text =
-                if let Ok(post) = post::Record::try_from_unknown(search_result.record.clone()) {
-                    post.text.clone()
-                } else {
-                    "".to_string()
-                };
-            let reply_count = search_result.reply_count.unwrap_or(0);
-            let repost_count = search_result.repost_count.unwrap_or(0);
-            let like_count = search_result.like_count.unwrap_or(0);
-
-            return Paragraph::new(vec![
-                Line::from(format!("{display_name} @{handle}")),
-                Line::from(parent_text),
-                Line::from(vec![
-                    Span::styled(
-                        format!("↩ {}", reply_count),
-                        Style::default().fg(Color::Gray),
-                    ),
+pub fn reply_input<'a>(state: &AppState, text: &Wrapped) -> Paragraph<'a> {
+    let Some(parent) = state.get_target_post() else {
+        return Paragraph::new("Error...");
+    };
+    let display_name = parent
+        .author
+        .display_name
+        .clone()
+        .unwrap_or_else(|| "".into());
+    let handle = parent.author.handle.to_string();
+    let parent_text = if let Ok(post) = post::Record::try_from_unknown(parent.record.clone()) {
+        post.text.clone()
+    } else {
+        "".to_string()
+    };
+    let reply_count = parent.reply_count.unwrap_or(0);
+    let repost_count = parent.repost_count.unwrap_or(0);
+    let like_count = parent.like_count.unwrap_or(0);
+
+    Paragraph::new(
+        vec![
+            Line::from(format!("{display_name} @{handle}")),
+            Line::from(parent_text),
+            Line::from(vec![
+                Span::styled(
+                    format!("↩ {}", reply_count),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled(
+                    format!("   🔁 {}", repost_count),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!("   ❤ {}", like_count),
+                    Style::default().fg(Color::Red),
+                ),
+            ]),
+            Line::from(""),
+        ]
+        .into_iter()
+        .chain(composer_lines(text))
+        .chain(attachments(state))
+        .collect::<Vec<_>>(),
+    )
+    .style(Style::default().fg(Color::White).bg(Color::Black))
+    .alignment(Alignment::Left)
+    .block(
+        Block::default()
+            .style(Style::default().fg(Color::White))
+            .borders(Borders::ALL)
+            .title("Reply")
+            .title_bottom(grapheme_counter(state))
+            .padding(Padding::new(1, 1, 1, 1)),
+    )
+}
+
+pub fn quote_input<'a>(state: &AppState, text: &Wrapped) -> Paragraph<'a> {
+    let Some(quoted) = state.get_target_post() else {
+        return Paragraph::new("Error...");
+    };
+    let display_name = quoted
+        .author
+        .display_name
+        .clone()
+        .unwrap_or_else(|| "".into());
+    let handle = quoted.author.handle.to_string();
+    let quoted_text = if let Ok(post) = post::Record::try_from_unknown(quoted.record.clone()) {
+        post.text.clone()
+    } else {
+        "".to_string()
+    };
+    let reply_count = quoted.reply_count.unwrap_or(0);
+    let repost_count = quoted.repost_count.unwrap_or(0);
+    let like_count = quoted.like_count.unwrap_or(0);
+
+    Paragraph::new(
+        vec![
+            Line::from(format!("{display_name} @{handle}")),
+            Line::from(quoted_text),
+            Line::from(vec![
+                Span::styled(
+                    format!("↩ {}", reply_count),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled(
+                    format!("   🔁 {}", repost_count),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!("   ❤ {}", like_count),
+                    Style::default().fg(Color::Red),
+                ),
+            ]),
+            Line::from(""),
+        ]
+        .into_iter()
+        .chain(composer_lines(text))
+        .chain(attachments(state))
+        .collect::<Vec<_>>(),
+    )
+    .style(Style::default().fg(Color::White).bg(Color::Black))
+    .alignment(Alignment::Left)
+    .block(
+        Block::default()
+            .style(Style::default().fg(Color::White))
+            .borders(Borders::ALL)
+            .title("Quote")
+            .title_bottom(grapheme_counter(state))
+            .padding(Padding::new(1, 1, 1, 1)),
+    )
+}
+
+pub fn delete_post<'a>(state: &AppState) -> Paragraph<'a> {
+    let Some(post) = state.get_post_to_delete() else {
+        return Paragraph::new("Error...");
+    };
+    let text = if let Ok(record) = post::Record::try_from_unknown(post.record.clone()) {
+        record.text.clone()
+    } else {
+        "".to_string()
+    };
+    let created_at = post
+        .indexed_at
+        .as_ref()
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M");
+
+    Paragraph::new(vec![
+        Line::from(Span::styled(
+            format!("Posted {created_at}"),
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(text),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y", Style::default().fg(Color::Red)),
+            Span::raw(": delete, "),
+            Span::styled("n", Style::default().fg(Color::Cyan)),
+            Span::raw(": cancel"),
+        ]),
+    ])
+    .style(Style::default().fg(Color::White).bg(Color::Black))
+    .wrap(Wrap { trim: false })
+    .block(
+        Block::default()
+            .style(Style::default().fg(Color::White))
+            .borders(Borders::ALL)
+            .title("Delete this post?")
+            .padding(Padding::new(1, 1, 1, 1)),
+    )
+}
+
+pub fn moderation<'a>(state: &AppState) -> Paragraph<'a> {
+    let Some(moderation) = state.get_moderation() else {
+        return Paragraph::new("Error...");
+    };
+    let (verb, handle, effect) = match &moderation {
+        Moderation::Mute(_, handle) => (
+            "Mute",
+            handle,
+            "Their posts are hidden from your timeline, search results and notifications. \
+             They are not told.",
+        ),
+        Moderation::Unmute(_, handle) => (
+            "Unmute",
+            handle,
+            "Their posts are shown again once reloaded.",
+        ),
+        Moderation::Block(_, handle) => (
+            "Block",
+            handle,
+            "Their posts are hidden, and they can no longer see or interact with yours.",
+        ),
+        Moderation::Unblock(_, handle, _) => (
+            "Unblock",
+            handle,
+            "Their posts are shown again once reloaded.",
+        ),
+    };
+
+    Paragraph::new(vec![
+        Line::from(effect),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("y", Style::default().fg(Color::Red)),
+            Span::raw(format!(": {}, ", verb.to_lowercase())),
+            Span::styled("n", Style::default().fg(Color::Cyan)),
+            Span::raw(": cancel"),
+        ]),
+    ])
+    .style(Style::default().fg(Color::White).bg(Color::Black))
+    .wrap(Wrap { trim: false })
+    .block(
+        Block::default()
+            .style(Style::default().fg(Color::White))
+            .borders(Borders::ALL)
+            .title(format!("{verb} @{}?", handle.as_str()))
+            .padding(Padding::new(1, 1, 1, 1)),
+    )
+}
+
+pub fn accounts<'a>(state: &AppState) -> List<'a> {
+    let current = state.get_account_index();
+    let list_items: Vec<ListItem> = state
+        .get_config()
+        .accounts()
+        .iter()
+        .enumerate()
+        .map(|(i, account)| {
+            if i == current {
+                ListItem::new(Line::from(vec![
                     Span::styled(
-                        format!("   🔁 {}", repost_count),
-                        Style::default().fg(Color::Green),
+                        account.identifier.clone(),
+                        Style::default().fg(Color::White),
                     ),
                     Span::styled(
-                        format!("   ❤ {}", like_count),
-                        Style::default().fg(Color::Red),
+                        format!(" @{}", state.get_handle().as_str()),
+                        Style::default().fg(Color::Gray),
                     ),
-                ]),
-                Line::from(""),
-                Line::from(text),
+                ]))
+            } else {
+                ListItem::new(Line::from(Span::styled(
+                    account.identifier.clone(),
+                    Style::default().fg(Color::White),
+                )))
+            }
+        })
+        .collect();
+
+    List::new(list_items)
+        .highlight_style(Style::default().bg(Color::Blue))
+        .block(
+            Block::default()
+                .title("Accounts")
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White).bg(Color::Black))
+                .padding(Padding::new(1, 1, 1, 1))
+                .border_type(BorderType::Plain),
+        )
+}
+
+pub fn login<'a>() -> Block<'a> {
+    Block::default()
+        .title("Login")
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::White).bg(Color::Black))
+        .border_type(BorderType::Plain)
+}
+
+pub fn login_input<'a>(state: &AppState, field: LoginField) -> Paragraph<'a> {
+    let value = state
+        .get_login_input(field)
+        .map(|input| input.value().to_string())
+        .unwrap_or_default();
+    let (title, text) = match field {
+        LoginField::Identifier => ("Identifier (email, handle or DID)", value),
+        LoginField::Password => ("Password", "*".repeat(value.chars().count())),
+        LoginField::AuthFactorToken => ("Sign-in code", value),
+    };
+    let border_color = if state.get_login_focus() == field {
+        Color::LightCyan
+    } else {
+        Color::White
+    };
+    Paragraph::new(text)
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Left)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(border_color))
+                .padding(Padding::new(1, 1, 0, 0)),
+        )
+}
+
+pub fn login_message<'a>(state: &AppState) -> Paragraph<'a> {
+    let line = if state.is_loading() {
+        Line::from(Span::styled(
+            "Logging in...",
+            Style::default()
+                .fg(Color::LightCyan)
+                .add_modifier(Modifier::BOLD),
+        ))
+    } else if let Some(error) = state.get_login_error() {
+        Line::from(Span::styled(error, Style::default().fg(Color::Red)))
+    } else {
+        Line::default()
+    };
+    Paragraph::new(line).wrap(Wrap { trim: true })
+}
+
+pub fn login_help<'a>() -> Paragraph<'a> {
+    Paragraph::new("Enter: log in / retry, Tab: next field, Ctrl+u: clear field, Esc: back / quit")
+        .style(Style::default().fg(Color::Gray))
+        .wrap(Wrap { trim: true })
+}
+
+pub fn toast<'a>(message: &Message) -> Paragraph<'a> {
+    let style = if message.is_error() {
+        Style::default().fg(Color::White).bg(Color::Red)
+    } else {
+        Style::default().fg(Color::Black).bg(Color::LightCyan)
+    };
+    Paragraph::new(Line::from(vec![
+        Span::styled(format!(" {} ", message.text), style),
+        Span::styled(" x: dismiss, m: messages", Style::default().fg(Color::Gray)),
+    ]))
+}
+
+pub fn messages<'a>(state: &AppState) -> List<'a> {
+    let list_items: Vec<ListItem> = state
+        .get_messages()
+        .into_iter()
+        .map(|message| {
+            let color = if message.is_error() {
+                Color::Red
+            } else {
+                Color::White
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    message.time.format("%H:%M:%S ").to_string(),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::styled(message.text, Style::default().fg(color)),
+            ]))
+        })
+        .collect();
+
+    List::new(list_items)
+        .highlight_style(Style::default().bg(Color::Blue))
+        .block(
+            Block::default()
+                .title("Messages")
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White).bg(Color::Black))
+                .padding(Padding::new(1, 1, 1, 1))
+                .border_type(BorderType::Plain),
+        )
+}
+
+pub fn links<'a>(state: &AppState) -> List<'a> {
+    let list_items: Vec<ListItem> = state
+        .get_selected_post_links()
+        .into_iter()
+        .map(|link| {
+            ListItem::new(vec![
+                Line::from(Span::styled(link.text, Style::default().fg(Color::White))),
+                Line::from(Span::styled(link.url, Style::default().fg(Color::Gray))),
             ])
-            .style(Style::default().fg(Color::White).bg(Color::Black))
-            .alignment(Alignment::Left)
-            .block(
-                Block::default()
-                    .style(Style::default().fg(Color::White))
-                    .borders(Borders::ALL)
-                    .title("Reply")
-                    .padding(Padding::new(1, 1, 1, 1)),
-            );
-        }
-    }
+        })
+        .collect();
+    let title = if list_items.is_empty() {
+        "Links (none in this post)"
+    } else {
+        "Links"
+    };
 
-    let current_feed = state.get_current_feed();
+    List::new(list_items)
+        .highlight_style(Style::default().bg(Color::Blue))
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White).bg(Color::Black))
+                .padding(Padding::new(1, 1, 1, 1))
+                .border_type(BorderType::Plain),
+        )
+}
 
-    if current_feed.is_none() {
-        return Paragraph::new("Error...");
-    }
+pub fn drafts<'a>(state: &AppState) -> List<'a> {
+    let list_items: Vec<ListItem> = state
+        .get_drafts()
+        .into_iter()
+        .map(|draft| {
+            let kind = match draft.kind {
+                DraftKind::Post => "Post",
+                DraftKind::Thread => "Thread",
+                DraftKind::ThreadReply { .. } => "Thread (rest)",
+                DraftKind::Reply { .. } => "Reply",
+                DraftKind::Quote { .. } => "Quote",
+            };
+            let mut header = vec![
+                Span::styled(format!("{kind} "), Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    draft.saved_at.format("%Y-%m-%d %H:%M").to_string(),
+                    Style::default().fg(Color::Gray),
+                ),
+            ];
+            if !draft.attachments.is_empty() {
+                header.push(Span::styled(
+                    format!("  🖼 {}", draft.attachments.len()),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            let first_line = draft.text.lines().next().unwrap_or_default().to_string();
+            ListItem::new(vec![
+                Line::from(header),
+                Line::from(Span::styled(first_line, Style::default().fg(Color::White))),
+            ])
+        })
+        .collect();
+    let title = if list_items.is_empty() {
+        "Drafts (none)"
+    } else {
+        "Drafts (Enter: open, s: send, d: delete)"
+    };
 
-    let current_feed = current_feed.unwrap();
-    let display_name = current_feed
-        .post
-        .author
-        .display_name
-        .clone()
-        .unwrap_or_else(|| "".into());
-    let handle = current_feed.post.author.handle.to_string();
-    let parent_text =
-        if let Ok(post) = post::Record::try_from_unknown(current_feed.post.record.clone()) {
-            post.text.clone()
-        } else {
-            "".to_string()
-        };
-    let reply_count = current_feed.post.reply_count.unwrap_or(0);
-    let repost_count = current_feed.post.repost_count.unwrap_or(0);
-    let like_count = current_feed.post.like_count.unwrap_or(0);
+    List::new(list_items)
+        .highlight_style(Style::default().bg(Color::Blue))
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White).bg(Color::Black))
+                .padding(Padding::new(1, 1, 1, 1))
+                .border_type(BorderType::Plain),
+        )
+}
 
+pub fn muted_words<'a>(state: &AppState) -> List<'a> {
+    let list_items: Vec<ListItem> = state
+        .get_muted_words()
+        .into_iter()
+        .map(|word| {
+            let target = if word.targets.iter().any(|target| target == TARGET_CONTENT) {
+                "text and tags"
+            } else {
+                "tags only"
+            };
+            let expiry = match &word.expires_at {
+                _ if muted_words::is_expired(&word) => {
+                    Span::styled("expired", Style::default().fg(Color::DarkGray))
+                }
+                Some(expires_at) => Span::styled(
+                    format!(
+                        "until {}",
+                        expires_at
+                            .as_ref()
+                            .with_timezone(&Local)
+                            .format("%Y-%m-%d %H:%M")
+                    ),
+                    Style::default().fg(Color::Gray),
+                ),
+                None => Span::styled("forever", Style::default().fg(Color::Gray)),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", word.value),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(format!("({target}) "), Style::default().fg(Color::Cyan)),
+                expiry,
+            ]))
+        })
+        .collect();
+    let title = if list_items.is_empty() {
+        "Muted words (none, a: add)"
+    } else {
+        "Muted words (a: add, d: delete)"
+    };
+
+    List::new(list_items)
+        .highlight_style(Style::default().bg(Color::Blue))
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White).bg(Color::Black))
+                .padding(Padding::new(1, 1, 1, 1))
+                .border_type(BorderType::Plain),
+        )
+}
+
+pub fn muted_word_prompt<'a>(prompt: &MutedWordPrompt) -> Paragraph<'a> {
+    let target = if prompt.tags_only {
+        "tags only"
+    } else {
+        "text and tags"
+    };
     Paragraph::new(vec![
-        Line::from(format!("{display_name} @{handle}")),
-        Line::from(parent_text),
+        Line::from(prompt.input.value().to_string()),
         Line::from(vec![
+            Span::styled("Tab: ", Style::default().fg(Color::Gray)),
+            Span::styled(target, Style::default().fg(Color::Cyan)),
+            Span::styled("  Ctrl+t: ", Style::default().fg(Color::Gray)),
             Span::styled(
-                format!("↩ {}", reply_count),
-                Style::default().fg(Color::Gray),
-            ),
-            Span::styled(
-                format!("   🔁 {}", repost_count),
-                Style::default().fg(Color::Green),
-            ),
-            Span::styled(
-                format!("   ❤ {}", like_count),
-                Style::default().fg(Color::Red),
+                prompt.duration.to_string(),
+                Style::default().fg(Color::Cyan),
             ),
         ]),
-        Line::from(""),
-        Line::from(text),
     ])
     .style(Style::default().fg(Color::White).bg(Color::Black))
     .alignment(Alignment::Left)
@@ -662,25 +1820,186 @@ pub fn reply_input<'a>(state: &AppState) -> Paragraph<'a> {
         Block::default()
             .style(Style::default().fg(Color::White))
             .borders(Borders::ALL)
-            .title("Reply")
+            .title("Mute a word or #tag (Enter: save, Esc: cancel)")
             .padding(Padding::new(1, 1, 1, 1)),
     )
 }
 
+pub fn preview<'a>(image: &PreviewImage) -> Block<'a> {
+    let title = if image.count > 1 {
+        format!("Image {}/{}", image.index, image.count)
+    } else {
+        "Image".to_string()
+    };
+
+    Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::White))
+        .border_type(BorderType::Plain)
+}
+
+pub fn preview_message<'a>(thumbnail: Option<&Thumbnail>) -> Paragraph<'a> {
+    let text = match thumbnail {
+        Some(Thumbnail::Failed) => "Image could not be loaded",
+        _ => "Loading image...",
+    };
+
+    Paragraph::new(text)
+        .style(Style::default().fg(Color::Gray))
+        .alignment(Alignment::Center)
+}
+
+/// Draws `image` scaled to fit `area` with upper half blocks, two pixels to a cell.
+pub fn halfblocks<'a>(image: &RgbImage, area: Rect) -> Paragraph<'a> {
+    let (_, (width, height)) = preview::fit(image.width(), image.height(), area, (1, 2));
+    let image = imageops::thumbnail(image, width, height);
+    let color = |pixel: &Rgb<u8>| Color::Rgb(pixel[0], pixel[1], pixel[2]);
+
+    let lines: Vec<Line> = (0..height)
+        .step_by(2)
+        .map(|y| {
+            let spans: Vec<Span> = (0..width)
+                .map(|x| {
+                    let mut style = Style::default().fg(color(image.get_pixel(x, y)));
+                    if y + 1 < height {
+                        style = style.bg(color(image.get_pixel(x, y + 1)));
+                    }
+                    Span::styled("▀", style)
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+
+    Paragraph::new(lines).alignment(Alignment::Center)
+}
+
 pub fn tabs<'a>(state: &AppState) -> Tabs<'a> {
     let titles: Vec<_> = [Tab::Home, Tab::Notifications, Tab::Search]
         .iter()
         .map(|t| format!("{}", t))
         .collect();
+    let handle = if state.is_initialized() {
+        format!("@{}", state.get_handle().as_str())
+    } else {
+        "".into()
+    };
 
     Tabs::new(titles)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .style(Style::default())
+                .title(handle)
                 .border_type(BorderType::Plain),
         )
         .select(state.get_tab() as usize)
         .style(Style::default().fg(Color::White))
         .highlight_style(Style::default().fg(Color::Cyan))
 }
+
+/// The lines of a post in a list: its author, text, embed and counts, followed by `border`.
+/// The author's name stands out for the post a thread was opened for, `is_anchor`.
+fn post_lines<'a>(post: &PostViewData, border: &str, is_anchor: bool) -> Vec<Line<'a>> {
+    let (text, facets, created_at) =
+        if let Ok(r) = post::Record::try_from_unknown(post.record.clone()) {
+            let c = &r.created_at;
+            (r.text.clone(), r.facets.clone(), format!("{:?}+0000", c))
+        } else {
+            ("".into(), None, "".into())
+        };
+    let display_name = post
+        .author
+        .display_name
+        .clone()
+        .unwrap_or_else(|| "".into());
+    let handle = post.author.handle.to_string();
+    let reply_count = post.reply_count.unwrap_or(0);
+    let repost_count = post.repost_count.unwrap_or(0);
+    let like_count = post.like_count.unwrap_or(0);
+    let duration_text = match DateTime::parse_from_str(&created_at, "%Y-%m-%dT%H:%M:%S%z") {
+        Ok(dt) => utils::get_duration_string(dt, Utc::now().fixed_offset()),
+        Err(_) => "".into(),
+    };
+    let name_style = if is_anchor {
+        Style::default()
+            .fg(Color::LightCyan)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::White)
+    };
+    let mut lines = vec![Line::from(vec![
+        Span::styled(format!("{display_name} "), name_style),
+        Span::styled(
+            format!("@{handle} {duration_text}"),
+            Style::default().fg(Color::Gray),
+        ),
+    ])];
+    lines.extend(rich_text(&text, facets));
+    lines.extend(post.embed.iter().flat_map(embed::lines));
+    lines.extend(vec![
+        Line::from(vec![
+            Span::styled(
+                format!("↩ {}", reply_count),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::styled(
+                format!("   🔁 {}", repost_count),
+                Style::default().fg(Color::Green),
+            ),
+            Span::styled(
+                format!("   ❤ {}", like_count),
+                Style::default().fg(Color::Red),
+            ),
+        ]),
+        Line::from(Span::styled(
+            border.to_string(),
+            Style::default().fg(Color::Gray),
+        )),
+    ]);
+    lines
+}
+
+/// A post or notification hidden because it matched a muted word.
+fn hidden_item<'a>(word: String, border: &str) -> ListItem<'a> {
+    ListItem::new(vec![
+        Line::from(Span::styled(
+            format!("hidden: matched '{word}' (Enter: show)"),
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(Span::styled(
+            border.to_string(),
+            Style::default().fg(Color::Gray),
+        )),
+    ])
+}
+
+/// Splits post text into lines, highlighting mentions, links and tags.
+fn rich_text<'a>(text: &str, facets: Option<Vec<facet::Main>>) -> Vec<Line<'a>> {
+    let mut lines = vec![Line::default()];
+    for segment in bsky::facet::rich_text(text, facets).segments() {
+        let style = if segment.mention().is_some() {
+            Style::default().fg(Color::LightBlue)
+        } else if segment.link().is_some() {
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::UNDERLINED)
+        } else if segment.tag().is_some() {
+            Style::default().fg(Color::LightMagenta)
+        } else {
+            Style::default()
+        };
+        for (i, part) in segment.text.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(Line::default());
+            }
+            if !part.is_empty() {
+                if let Some(line) = lines.last_mut() {
+                    line.push_span(Span::styled(part.to_string(), style));
+                }
+            }
+        }
+    }
+    lines
+}
@@ -0,0 +1,139 @@
+use std::io::{Cursor, Write};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use crossterm::{
+    cursor::{MoveTo, RestorePosition, SavePosition},
+    queue,
+};
+use eyre::{eyre, Result};
+use image::{imageops, ImageFormat, RgbImage};
+use ratatui::layout::Rect;
+
+use crate::app::preview::ImageProtocol;
+
+/// Size of the base64 chunks images are sent to kitty in.
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Where [`super::render`] left room for the preview thumbnail to be drawn with a terminal
+/// graphics protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphicsArea {
+    pub protocol: ImageProtocol,
+    pub url: String,
+    /// The cells the image covers.
+    pub area: Rect,
+    /// The size the image is scaled to.
+    pub pixels: (u32, u32),
+}
+
+/// Draws the preview thumbnail over the frame ratatui drew, and keeps track of it so that it is
+/// only sent again when it changes.
+#[derive(Default)]
+pub struct Graphics {
+    shown: Option<GraphicsArea>,
+}
+
+impl Graphics {
+    /// Whether the screen has to be drawn again from scratch before showing `next`.
+    ///
+    /// ratatui skips the cells under an image, so they are stale once it goes away.
+    pub fn needs_clear(&self, next: Option<&GraphicsArea>) -> bool {
+        self.shown.is_some() && self.shown.as_ref() != next
+    }
+
+    /// Shows `next` (with `image` as its contents) in place of the image shown before.
+    pub fn show<W: Write>(
+        &mut self,
+        w: &mut W,
+        next: Option<GraphicsArea>,
+        image: Option<&RgbImage>,
+    ) -> Result<()> {
+        if self.shown == next {
+            return Ok(());
+        }
+        if let Some(shown) = self.shown.take() {
+            if shown.protocol == ImageProtocol::Kitty {
+                // Delete all images placed on the screen.
+                write!(w, "\x1b_Ga=d,d=A,q=2\x1b\\")?;
+            }
+        }
+        if let (Some(next), Some(image)) = (&next, image) {
+            // An image that cannot be encoded is left out rather than taking the UI down.
+            if let Ok(data) = encode(next, image) {
+                queue!(w, SavePosition, MoveTo(next.area.x, next.area.y))?;
+                w.write_all(data.as_bytes())?;
+                queue!(w, RestorePosition)?;
+            }
+            self.shown = Some(next.clone());
+        }
+        w.flush()?;
+
+        Ok(())
+    }
+}
+
+fn encode(graphics: &GraphicsArea, image: &RgbImage) -> Result<String> {
+    let (width, height) = graphics.pixels;
+    let image = imageops::thumbnail(image, width, height);
+    match graphics.protocol {
+        ImageProtocol::Kitty => Ok(kitty(&image, graphics.area)),
+        ImageProtocol::Iterm2 => iterm2(&image, graphics.area),
+        ImageProtocol::Sixel => sixel(&image),
+        ImageProtocol::Auto | ImageProtocol::Halfblocks => Ok(String::new()),
+    }
+}
+
+/// Sends the raw RGB pixels, placed over `area` without moving the cursor.
+fn kitty(image: &RgbImage, area: Rect) -> String {
+    let data = STANDARD.encode(image.as_raw());
+    let chunks: Vec<&str> = data
+        .as_bytes()
+        .chunks(KITTY_CHUNK_SIZE)
+        // Base64 is ASCII, so any split is valid UTF-8.
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+        .collect();
+
+    let mut escape = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        if i == 0 {
+            escape.push_str(&format!(
+                "\x1b_Ga=T,f=24,s={},v={},c={},r={},C=1,q=2,m={more};{chunk}\x1b\\",
+                image.width(),
+                image.height(),
+                area.width,
+                area.height,
+            ));
+        } else {
+            escape.push_str(&format!("\x1b_Gm={more};{chunk}\x1b\\"));
+        }
+    }
+    escape
+}
+
+/// Sends the image as a PNG file sized in cells.
+fn iterm2(image: &RgbImage, area: Rect) -> Result<String> {
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+    Ok(format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        png.len(),
+        area.width,
+        area.height,
+        STANDARD.encode(&png),
+    ))
+}
+
+fn sixel(image: &RgbImage) -> Result<String> {
+    icy_sixel::sixel_string(
+        image.as_raw(),
+        image.width() as i32,
+        image.height() as i32,
+        icy_sixel::PixelFormat::RGB888,
+        icy_sixel::DiffusionMethod::Stucki,
+        icy_sixel::MethodForLargest::Auto,
+        icy_sixel::MethodForRep::Auto,
+        icy_sixel::Quality::HIGH,
+    )
+    .map_err(|e| eyre!("{e}"))
+}
@@ -24,6 +24,22 @@ pub fn body(rect: Rect) -> Rc<[Rect]> {
         .split(rect)
 }
 
+/// The profile view: the profile header, `header_height` lines tall, above the author feed.
+pub fn profile(rect: Rect, header_height: u16) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(header_height), Constraint::Min(0)])
+        .split(rect)
+}
+
+/// A follow list with its filter box above it.
+pub fn follow_list(rect: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(rect)
+}
+
 pub fn popup(percent_x: u16, percent_y: u16, rect: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -51,12 +67,41 @@ pub fn popup(percent_x: u16, percent_y: u16, rect: Rect) -> Rect {
 }
 
 pub fn input_popup(rect: Rect) -> Rect {
+    centered_popup(rect, 5)
+}
+
+/// The post composer, with `lines` lines of text and attached images.
+pub fn post_popup(rect: Rect, lines: usize) -> Rect {
+    centered_popup(rect, 4 + lines as u16)
+}
+
+/// The reply composer, with `lines` lines of text and attached images below the parent post.
+pub fn reply_popup(rect: Rect, lines: usize) -> Rect {
+    centered_popup(rect, 8 + lines as u16)
+}
+
+/// The thread composer, with `lines` lines of text and attached images, above the preview of
+/// the posts it is split into.
+pub fn thread_popup(rect: Rect, lines: usize) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(4 + lines as u16), Constraint::Min(0)])
+        .split(popup(60, 80, rect))
+}
+
+/// Width of the text in the post and reply composers.
+pub fn composer_width(rect: Rect) -> u16 {
+    // Borders and padding on both sides.
+    centered_popup(rect, 0).width.saturating_sub(4)
+}
+
+fn centered_popup(rect: Rect, height: u16) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
             [
                 Constraint::Percentage(50),
-                Constraint::Length(5),
+                Constraint::Length(height),
                 Constraint::Percentage(50),
             ]
             .as_ref(),
@@ -76,28 +121,33 @@ pub fn input_popup(rect: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
-pub fn reply_popup(rect: Rect) -> Rect {
-    let popup_layout = Layout::default()
+pub fn login(rect: Rect, with_auth_factor_token: bool) -> Rc<[Rect]> {
+    Layout::default()
         .direction(Direction::Vertical)
-        .constraints(
-            [
-                Constraint::Percentage(50),
-                Constraint::Length(10),
-                Constraint::Percentage(50),
-            ]
-            .as_ref(),
-        )
-        .split(rect);
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(if with_auth_factor_token { 3 } else { 0 }),
+            Constraint::Min(2),
+            Constraint::Length(2),
+        ])
+        .margin(1)
+        .split(rect)
+}
+
+pub fn toast(rect: Rect) -> Rect {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .horizontal_margin(2)
+        .vertical_margin(1)
+        .split(rect)[1]
+}
 
+/// Splits the body into the list and the image preview pane.
+pub fn preview(rect: Rect) -> Rc<[Rect]> {
     Layout::default()
         .direction(Direction::Horizontal)
-        .constraints(
-            [
-                Constraint::Percentage(20),
-                Constraint::Percentage(60),
-                Constraint::Percentage(20),
-            ]
-            .as_ref(),
-        )
-        .split(popup_layout[1])[1]
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(rect)
 }
@@ -1,16 +1,38 @@
 mod draw;
+pub mod graphics;
 mod layout;
 
-use ratatui::{backend::Backend, layout::Position, widgets::Clear, Frame};
+use ratatui::{
+    backend::Backend,
+    layout::{Position, Rect},
+    widgets::Clear,
+    Frame,
+};
 
-use crate::app::{state::Tab, App};
+use self::graphics::GraphicsArea;
+use crate::app::{
+    composer::Wrapped,
+    preview::{self, ImageProtocol, PreviewImage, Thumbnail},
+    state::{LoginField, Mode, Tab},
+    App,
+};
 
-pub fn render<B>(f: &mut Frame, app: &App)
+/// Most lines of text the composer shows before scrolling.
+const MAX_COMPOSER_LINES: usize = 10;
+
+/// Draws the app. Returns where the preview thumbnail is to be drawn with a terminal graphics
+/// protocol, if it is shown and not drawn with half blocks.
+pub fn render<B>(f: &mut Frame, app: &App) -> Option<GraphicsArea>
 where
     B: Backend,
 {
     let size = f.area();
 
+    if app.state.is_login() {
+        render_login(f, app);
+        return None;
+    }
+
     let main_chunks = layout::main(size);
     let header_chunks = layout::header(main_chunks[0]);
     let body_chunks = layout::body(main_chunks[1]);
@@ -24,11 +46,50 @@ where
     let tabs = draw::tabs(app.state());
     f.render_widget(tabs, body_chunks[0]);
 
+    let mut list_area = body_chunks[1];
+    let mut graphics = None;
+    if let (Some(protocol), Some(image), false) = (
+        app.state.get_image_protocol(),
+        app.state.get_preview_image(),
+        app.state.is_loading(),
+    ) {
+        let chunks = layout::preview(body_chunks[1]);
+        list_area = chunks[0];
+        graphics = render_preview(f, app, protocol, &image, chunks[1]);
+    }
+
     if app.state.is_loading() {
         let popup = draw::loading();
         let area = layout::popup(60, 20, size);
         f.render_widget(Clear, area);
         f.render_widget(popup, area);
+    } else if let Some(thread) = app.state.get_thread() {
+        let body = draw::thread(app.state());
+        f.render_stateful_widget(body, list_area, &mut thread.list_state.clone());
+    } else if let Some(profile) = app.state.get_profile() {
+        let chunks = layout::profile(
+            list_area,
+            draw::profile_height(&profile, list_area.height / 2),
+        );
+        f.render_widget(draw::profile(app.state(), &profile), chunks[0]);
+        let body = draw::author_feed(&profile);
+        f.render_stateful_widget(body, chunks[1], &mut profile.list_state.clone());
+    } else if let Some(list) = app.state.get_follow_list() {
+        let list_area = if list.is_filtering || !list.filter.value().is_empty() {
+            let chunks = layout::follow_list(list_area);
+            f.render_widget(draw::follow_filter(&list), chunks[0]);
+            if list.is_filtering {
+                f.set_cursor_position(Position::new(
+                    chunks[0].x + 2 + list.filter.visual_cursor() as u16,
+                    chunks[0].y + 1,
+                ));
+            }
+            chunks[1]
+        } else {
+            list_area
+        };
+        let body = draw::follow_list(app.state(), &list);
+        f.render_stateful_widget(body, list_area, &mut list.list_state.clone());
     } else {
         match app.state.get_tab() {
             Tab::Home => {
@@ -36,7 +97,7 @@ where
                 app.state
                     .get_tl_list_state()
                     .select(Some(app.state.get_tl_list_position()));
-                f.render_stateful_widget(body, body_chunks[1], &mut app.state.get_tl_list_state());
+                f.render_stateful_widget(body, list_area, &mut app.state.get_tl_list_state());
             }
             Tab::Notifications => {
                 let body = draw::notifications(app.state());
@@ -45,7 +106,7 @@ where
                     .select(Some(app.state.get_notifications_list_position()));
                 f.render_stateful_widget(
                     body,
-                    body_chunks[1],
+                    list_area,
                     &mut app.state.get_notifications_list_state(),
                 );
             }
@@ -54,11 +115,7 @@ where
                 app.state
                     .get_search_list_state()
                     .select(Some(app.state.get_search_list_position()));
-                f.render_stateful_widget(
-                    body,
-                    body_chunks[1],
-                    &mut app.state.get_search_list_state(),
-                );
+                f.render_stateful_widget(body, list_area, &mut app.state.get_search_list_state());
             }
         };
     }
@@ -70,25 +127,133 @@ where
         f.render_widget(popup, area);
     }
 
-    if app.state.is_post_mode() {
-        let popup = draw::post_input(app.state());
-        let area = layout::input_popup(size);
+    if app.state.is_accounts_mode() {
+        let popup = draw::accounts(app.state());
+        let area = layout::popup(60, 40, size);
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(popup, area, &mut app.state.get_accounts_list_state());
+    }
+
+    if app.state.is_links_mode() {
+        let popup = draw::links(app.state());
+        let area = layout::popup(60, 40, size);
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(popup, area, &mut app.state.get_links_list_state());
+    }
+
+    if app.state.is_drafts_mode() {
+        let popup = draw::drafts(app.state());
+        let area = layout::popup(60, 40, size);
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(popup, area, &mut app.state.get_drafts_list_state());
+    }
+
+    if app.state.is_delete_post_mode() {
+        let popup = draw::delete_post(app.state());
+        let area = layout::popup(50, 30, size);
+        f.render_widget(Clear, area);
+        f.render_widget(popup, area);
+    }
+
+    if app.state.is_muted_words_mode() {
+        let popup = draw::muted_words(app.state());
+        let area = layout::popup(60, 40, size);
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(popup, area, &mut app.state.get_muted_words_list_state());
+    }
+
+    if let Some(prompt) = app
+        .state
+        .get_muted_word_prompt()
+        .filter(|_| app.state.is_muted_words_mode())
+    {
+        let popup = draw::muted_word_prompt(&prompt);
+        let area = layout::post_popup(size, 2);
         f.render_widget(Clear, area);
         f.render_widget(popup, area);
         f.set_cursor_position(Position::new(
-            area.x + 2 + app.state.get_input().visual_cursor() as u16,
+            area.x + 2 + prompt.input.visual_cursor() as u16,
             area.y + 2,
         ));
     }
 
+    if app.state.is_moderate_mode() {
+        let popup = draw::moderation(app.state());
+        let area = layout::popup(50, 30, size);
+        f.render_widget(Clear, area);
+        f.render_widget(popup, area);
+    }
+
+    if app.state.is_messages_mode() {
+        let popup = draw::messages(app.state());
+        let area = layout::popup(60, 40, size);
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(popup, area, &mut app.state.get_messages_list_state());
+    }
+
+    if let Some(message) = app.state.get_toast() {
+        let area = layout::toast(size);
+        f.render_widget(Clear, area);
+        f.render_widget(draw::toast(&message), area);
+    }
+
+    if app.state.is_post_mode() {
+        let text = composer_text(app, size);
+        let popup = draw::post_input(app.state(), &text);
+        let area = layout::post_popup(size, text.lines.len() + app.state.get_attachments().len());
+        f.render_widget(Clear, area);
+        f.render_widget(popup, area);
+        f.set_cursor_position(Position::new(
+            area.x + 2 + text.cursor.0 as u16,
+            area.y + 2 + text.cursor.1 as u16,
+        ));
+    }
+
+    if app.state.is_new_thread_mode() {
+        let text = composer_text(app, size);
+        let areas =
+            layout::thread_popup(size, text.lines.len() + app.state.get_attachments().len());
+        f.render_widget(Clear, areas[0].union(areas[1]));
+        f.render_widget(draw::thread_input(app.state(), &text), areas[0]);
+        f.render_widget(draw::thread_preview(app.state()), areas[1]);
+        f.set_cursor_position(Position::new(
+            areas[0].x + 2 + text.cursor.0 as u16,
+            areas[0].y + 2 + text.cursor.1 as u16,
+        ));
+    }
+
     if app.state.is_reply_mode() {
-        let popup = draw::reply_input(app.state());
-        let area = layout::reply_popup(size);
+        let text = composer_text(app, size);
+        let popup = draw::reply_input(app.state(), &text);
+        let area = layout::reply_popup(size, text.lines.len() + app.state.get_attachments().len());
         f.render_widget(Clear, area);
         f.render_widget(popup, area);
         f.set_cursor_position(Position::new(
-            area.x + 2 + app.state.get_input().visual_cursor() as u16,
-            area.y + 6,
+            area.x + 2 + text.cursor.0 as u16,
+            area.y + 6 + text.cursor.1 as u16,
+        ));
+    }
+
+    if app.state.is_quote_mode() {
+        let text = composer_text(app, size);
+        let popup = draw::quote_input(app.state(), &text);
+        let area = layout::reply_popup(size, text.lines.len() + app.state.get_attachments().len());
+        f.render_widget(Clear, area);
+        f.render_widget(popup, area);
+        f.set_cursor_position(Position::new(
+            area.x + 2 + text.cursor.0 as u16,
+            area.y + 6 + text.cursor.1 as u16,
+        ));
+    }
+
+    if let Some(prompt) = app.state.get_attachment_prompt() {
+        let popup = draw::attachment_prompt(app.state(), &prompt);
+        let area = layout::input_popup(size);
+        f.render_widget(Clear, area);
+        f.render_widget(popup, area);
+        f.set_cursor_position(Position::new(
+            area.x + 2 + prompt.input().visual_cursor() as u16,
+            area.y + 2,
         ));
     }
 
@@ -102,6 +267,117 @@ where
             area.y + 2,
         ));
     }
+
+    if app.state.is_go_to_profile_mode() {
+        let popup = draw::profile_input(app.state());
+        let area = layout::input_popup(size);
+        f.render_widget(Clear, area);
+        f.render_widget(popup, area);
+        f.set_cursor_position(Position::new(
+            area.x + 2 + app.state.get_input().visual_cursor() as u16,
+            area.y + 2,
+        ));
+    }
+
+    // Graphics would be drawn over popups, so they are only shown while none is open.
+    let graphics = graphics.filter(|_| app.state.get_mode() == Mode::Normal)?;
+    // Keep ratatui from drawing over the image.
+    for y in graphics.area.top()..graphics.area.bottom() {
+        for x in graphics.area.left()..graphics.area.right() {
+            f.buffer_mut()[(x, y)].set_skip(true);
+        }
+    }
+    Some(graphics)
+}
+
+fn render_preview(
+    f: &mut Frame,
+    app: &App,
+    protocol: ImageProtocol,
+    image: &PreviewImage,
+    area: Rect,
+) -> Option<GraphicsArea> {
+    let block = draw::preview(image);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    match app.get_thumbnail(&image.url) {
+        Some(Thumbnail::Loaded(thumbnail)) if protocol == ImageProtocol::Halfblocks => {
+            f.render_widget(draw::halfblocks(thumbnail, inner), inner);
+            None
+        }
+        Some(Thumbnail::Loaded(thumbnail)) => {
+            let (area, pixels) = preview::fit(
+                thumbnail.width(),
+                thumbnail.height(),
+                inner,
+                preview::cell_size(),
+            );
+            Some(GraphicsArea {
+                protocol,
+                url: image.url.clone(),
+                area,
+                pixels,
+            })
+        }
+        thumbnail => {
+            f.render_widget(draw::preview_message(thumbnail), inner);
+            None
+        }
+    }
+}
+
+/// The text being written, wrapped to the composer and scrolled to keep the cursor in view.
+fn composer_text(app: &App, size: Rect) -> Wrapped {
+    app.state
+        .get_composer()
+        .wrap(layout::composer_width(size))
+        .scrolled(MAX_COMPOSER_LINES)
+}
+
+fn render_login(f: &mut Frame, app: &App) {
+    let size = f.area();
+    let main_chunks = layout::main(size);
+    let header_chunks = layout::header(main_chunks[0]);
+
+    let title = draw::title();
+    f.render_widget(title, header_chunks[0]);
+
+    let area = layout::popup(60, 60, size);
+    let with_auth_factor_token = app
+        .state
+        .get_login_input(LoginField::AuthFactorToken)
+        .is_some();
+    let chunks = layout::login(area, with_auth_factor_token);
+    f.render_widget(Clear, area);
+    f.render_widget(draw::login(), area);
+
+    let fields = [
+        LoginField::Identifier,
+        LoginField::Password,
+        LoginField::AuthFactorToken,
+    ];
+    for (field, chunk) in fields.iter().copied().zip(chunks.iter()) {
+        if field == LoginField::AuthFactorToken && !with_auth_factor_token {
+            continue;
+        }
+        f.render_widget(draw::login_input(app.state(), field), *chunk);
+    }
+    f.render_widget(draw::login_message(app.state()), chunks[3]);
+    f.render_widget(draw::login_help(), chunks[4]);
+
+    let focus = app.state.get_login_focus();
+    if let Some(input) = app.state.get_login_input(focus) {
+        let chunk = match focus {
+            LoginField::Identifier => chunks[0],
+            LoginField::Password => chunks[1],
+            LoginField::AuthFactorToken => chunks[2],
+        };
+        f.set_cursor_position(Position::new(
+            chunk.x + 2 + input.visual_cursor() as u16,
+            chunk.y + 1,
+        ));
+    }
 }
 
 pub fn render_splash<B>(f: &mut Frame, splash_text: String)
@@ -0,0 +1,90 @@
+use std::fmt::{self, Debug, Display};
+
+use atrium_xrpc::{error::XrpcErrorKind, http::StatusCode};
+use bsky_sdk::error::GenericXrpcError;
+
+/// A failed call to the API.
+///
+/// Every call returns its own `atrium_xrpc::Error<E>`, which cannot be told apart once it is
+/// boxed in an `eyre::Report`, so calls are mapped to this to keep what the error was.
+#[derive(Debug)]
+pub struct ApiError {
+    /// The HTTP status, if the server answered.
+    pub status: Option<StatusCode>,
+    /// The XRPC error name, e.g. `ExpiredToken`.
+    pub name: Option<String>,
+    /// Whether the server could not be reached.
+    pub network: bool,
+    message: String,
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl<E: Debug + Display> From<atrium_xrpc::Error<E>> for ApiError {
+    fn from(e: atrium_xrpc::Error<E>) -> Self {
+        let message = e.to_string();
+        match e {
+            atrium_xrpc::Error::XrpcResponse(response) => Self {
+                status: Some(response.status),
+                name: response.error.and_then(|error| match error {
+                    // Errors declared in the lexicon are shown as `Name` or `Name: message`.
+                    XrpcErrorKind::Custom(error) => {
+                        let error = error.to_string();
+                        error.split(':').next().map(str::to_string)
+                    }
+                    XrpcErrorKind::Undefined(body) => body.error,
+                }),
+                network: false,
+                message,
+            },
+            atrium_xrpc::Error::HttpClient(_) => Self {
+                status: None,
+                name: None,
+                network: true,
+                message,
+            },
+            _ => Self {
+                status: None,
+                name: None,
+                network: false,
+                message,
+            },
+        }
+    }
+}
+
+impl From<bsky_sdk::Error> for ApiError {
+    fn from(e: bsky_sdk::Error) -> Self {
+        let message = e.to_string();
+        match e {
+            bsky_sdk::Error::Xrpc(error) => match *error {
+                GenericXrpcError::Response { status, error } => Self {
+                    status: Some(status),
+                    // The body is shown as `Name: message`.
+                    name: error.and_then(|error| error.split(':').next().map(str::to_string)),
+                    network: false,
+                    message,
+                },
+                // bsky-sdk only keeps the `Debug` output of errors without a response.
+                GenericXrpcError::Other(error) => Self {
+                    status: None,
+                    name: None,
+                    network: error.starts_with("HttpClient("),
+                    message,
+                },
+            },
+            _ => Self {
+                status: None,
+                name: None,
+                network: false,
+                message,
+            },
+        }
+    }
+}
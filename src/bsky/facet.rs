@@ -0,0 +1,328 @@
+use std::sync::OnceLock;
+
+use atrium_api::{
+    app::bsky::richtext::facet,
+    com::atproto::identity::resolve_handle,
+    types::{string::Handle, Union},
+};
+use bsky_sdk::{rich_text::RichText, BskyAgent};
+use regex::Regex;
+
+static RE_MENTION: OnceLock<Regex> = OnceLock::new();
+static RE_URL: OnceLock<Regex> = OnceLock::new();
+static RE_TAG: OnceLock<Regex> = OnceLock::new();
+static RE_TRAILING_PUNCTUATION: OnceLock<Regex> = OnceLock::new();
+
+/// Longest tag (in bytes, without the `#`) Bluesky accepts.
+const MAX_TAG_LEN: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Feature {
+    /// A handle, without the `@`. Resolved to a DID before posting.
+    Mention(String),
+    Link(String),
+    /// A tag, without the `#`.
+    Tag(String),
+}
+
+/// A mention, link or tag found in post text, with its position as UTF-8 byte offsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedFacet {
+    pub feature: Feature,
+    pub byte_start: usize,
+    pub byte_end: usize,
+}
+
+/// Finds the mentions, links and tags in `text`, ordered by position.
+///
+/// This follows the detection rules of the official Bluesky app so that posts look the same
+/// wherever they were written.
+pub fn detect(text: &str) -> Vec<DetectedFacet> {
+    let mut facets = Vec::new();
+
+    let re = RE_MENTION.get_or_init(|| {
+        Regex::new(
+            r"(?:^|\s|\()@(([a-zA-Z0-9]([a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?\.)+[a-zA-Z]([a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?)\b",
+        )
+        .unwrap()
+    });
+    for capture in re.captures_iter(text) {
+        if let Some(handle) = capture.get(1) {
+            facets.push(DetectedFacet {
+                feature: Feature::Mention(handle.as_str().to_string()),
+                byte_start: handle.start() - 1,
+                byte_end: handle.end(),
+            });
+        }
+    }
+
+    let re = RE_URL.get_or_init(|| {
+        Regex::new(
+            r"(?:^|\s|\()((?:https?://\S+)|(?:(?<domain>[a-z][a-z0-9]*(?:\.[a-z0-9]+)+)\S*))",
+        )
+        .unwrap()
+    });
+    for capture in re.captures_iter(text) {
+        let Some(link) = capture.get(1) else {
+            continue;
+        };
+        let mut uri = match capture.name("domain") {
+            Some(domain) => {
+                if !psl::suffix(domain.as_str().as_bytes()).is_some_and(|s| s.is_known()) {
+                    continue;
+                }
+                format!("https://{}", link.as_str())
+            }
+            None => link.as_str().to_string(),
+        };
+        let mut byte_end = link.end();
+        if uri.ends_with(['.', ',', ';', ':', '!', '?'])
+            || (uri.ends_with(')') && !uri.contains('('))
+        {
+            uri.pop();
+            byte_end -= 1;
+        }
+        facets.push(DetectedFacet {
+            feature: Feature::Link(uri),
+            byte_start: link.start(),
+            byte_end,
+        });
+    }
+
+    let re = RE_TAG.get_or_init(|| {
+        Regex::new(
+            r"(?:^|\s)([#＃])([^\s\u00AD\u2060\u200A\u200B\u200C\u200D\u20e2]*[^\d\s\p{P}\u00AD\u2060\u200A\u200B\u200C\u200D\u20e2]+[^\s\u00AD\u2060\u200A\u200B\u200C\u200D\u20e2]*)?",
+        )
+        .unwrap()
+    });
+    let trailing_punctuation =
+        RE_TRAILING_PUNCTUATION.get_or_init(|| Regex::new(r"\p{P}+$").unwrap());
+    for capture in re.captures_iter(text) {
+        let (Some(hash), Some(tag)) = (capture.get(1), capture.get(2)) else {
+            continue;
+        };
+        let tag = trailing_punctuation.replace(tag.as_str(), "");
+        if tag.starts_with('\u{fe0f}') || tag.len() > MAX_TAG_LEN {
+            continue;
+        }
+        facets.push(DetectedFacet {
+            feature: Feature::Tag(tag.to_string()),
+            byte_start: hash.start(),
+            byte_end: hash.end() + tag.len(),
+        });
+    }
+
+    facets.sort_by_key(|facet| facet.byte_start);
+    facets
+}
+
+/// Builds the `app.bsky.richtext.facet` entries for `text`, resolving mentioned handles to DIDs.
+///
+/// Mentions of handles that cannot be resolved are left as plain text.
+pub async fn facets(agent: &BskyAgent, text: &str) -> Vec<facet::Main> {
+    let mut facets = Vec::new();
+    for detected in detect(text) {
+        let feature = match detected.feature {
+            Feature::Mention(handle) => {
+                let Ok(handle) = Handle::new(handle) else {
+                    continue;
+                };
+                let did = match agent
+                    .api
+                    .com
+                    .atproto
+                    .identity
+                    .resolve_handle(resolve_handle::ParametersData { handle }.into())
+                    .await
+                {
+                    Ok(output) => output.data.did,
+                    Err(_) => continue,
+                };
+                facet::MainFeaturesItem::Mention(Box::new(facet::MentionData { did }.into()))
+            }
+            Feature::Link(uri) => {
+                facet::MainFeaturesItem::Link(Box::new(facet::LinkData { uri }.into()))
+            }
+            Feature::Tag(tag) => {
+                facet::MainFeaturesItem::Tag(Box::new(facet::TagData { tag }.into()))
+            }
+        };
+        facets.push(
+            facet::MainData {
+                features: vec![Union::Refs(feature)],
+                index: facet::ByteSliceData {
+                    byte_start: detected.byte_start,
+                    byte_end: detected.byte_end,
+                }
+                .into(),
+            }
+            .into(),
+        );
+    }
+    facets
+}
+
+/// Builds a [`RichText`] for received post text, dropping facets whose byte ranges do not fit the
+/// text so that slicing it by them cannot fail.
+pub fn rich_text(text: &str, facets: Option<Vec<facet::Main>>) -> RichText {
+    let mut facets: Vec<facet::Main> = facets
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|facet| {
+            let (start, end) = (facet.index.byte_start, facet.index.byte_end);
+            start < end
+                && end <= text.len()
+                && text.is_char_boundary(start)
+                && text.is_char_boundary(end)
+        })
+        .collect();
+    facets.sort_by_key(|facet| facet.index.byte_start);
+    RichText::new(text, Some(facets).filter(|facets| !facets.is_empty()))
+}
+
+/// A link, mention or tag in a received post, with the URL it points to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FacetLink {
+    /// The text the facet covers, e.g. `@alice.bsky.social`.
+    pub text: String,
+    pub url: String,
+}
+
+/// Lists the targets of the links, mentions and tags in received post text.
+pub fn links(text: &str, facets: Option<Vec<facet::Main>>) -> Vec<FacetLink> {
+    rich_text(text, facets)
+        .segments()
+        .into_iter()
+        .filter_map(|segment| {
+            let url = if let Some(link) = segment.link() {
+                link.data.uri
+            } else if let Some(mention) = segment.mention() {
+                format!("https://bsky.app/profile/{}", mention.data.did.as_str())
+            } else if let Some(tag) = segment.tag() {
+                format!("https://bsky.app/hashtag/{}", tag.data.tag)
+            } else {
+                return None;
+            };
+            Some(FacetLink {
+                text: segment.text,
+                url,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slices(text: &str) -> Vec<(&str, Feature)> {
+        detect(text)
+            .into_iter()
+            .map(|facet| (&text[facet.byte_start..facet.byte_end], facet.feature))
+            .collect()
+    }
+
+    #[test]
+    fn detects_mentions_links_and_tags() {
+        assert_eq!(
+            slices("hi @alice.bsky.social see https://example.com/a?b=c #rust"),
+            vec![
+                (
+                    "@alice.bsky.social",
+                    Feature::Mention("alice.bsky.social".into())
+                ),
+                (
+                    "https://example.com/a?b=c",
+                    Feature::Link("https://example.com/a?b=c".into())
+                ),
+                ("#rust", Feature::Tag("rust".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn strips_trailing_punctuation() {
+        assert_eq!(
+            slices("read https://example.com. (#tag!)"),
+            vec![(
+                "https://example.com",
+                Feature::Link("https://example.com".into())
+            )]
+        );
+        assert_eq!(slices("#tag!"), vec![("#tag", Feature::Tag("tag".into()))]);
+    }
+
+    #[test]
+    fn links_bare_domains_with_known_suffix() {
+        assert_eq!(
+            slices("see example.com/path and foo.notatld"),
+            vec![(
+                "example.com/path",
+                Feature::Link("https://example.com/path".into())
+            )]
+        );
+    }
+
+    #[test]
+    fn uses_utf8_byte_offsets_for_multibyte_text() {
+        let text = "こんにちは @alice.bsky.social さん https://example.com #日本語 ＃タグ";
+        let facets = detect(text);
+
+        assert_eq!(facets.len(), 4);
+        assert_eq!(facets[0].byte_start, "こんにちは ".len());
+        assert_eq!(
+            slices(text),
+            vec![
+                (
+                    "@alice.bsky.social",
+                    Feature::Mention("alice.bsky.social".into())
+                ),
+                (
+                    "https://example.com",
+                    Feature::Link("https://example.com".into())
+                ),
+                ("#日本語", Feature::Tag("日本語".into())),
+                ("＃タグ", Feature::Tag("タグ".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_numeric_tags_and_emails() {
+        assert!(slices("#123 mail@example").is_empty());
+    }
+
+    fn received_facet(byte_start: usize, byte_end: usize, uri: &str) -> facet::Main {
+        facet::MainData {
+            features: vec![Union::Refs(facet::MainFeaturesItem::Link(Box::new(
+                facet::LinkData { uri: uri.into() }.into(),
+            )))],
+            index: facet::ByteSliceData {
+                byte_start,
+                byte_end,
+            }
+            .into(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn links_drops_facets_outside_the_text() {
+        let text = "リンク example.com";
+        let start = "リンク ".len();
+        let facets = vec![
+            received_facet(start, text.len(), "https://example.com"),
+            // Ends inside a multibyte character.
+            received_facet(0, 2, "https://broken.example"),
+            received_facet(start, text.len() + 10, "https://broken.example"),
+        ];
+
+        assert_eq!(
+            links(text, Some(facets)),
+            vec![FacetLink {
+                text: "example.com".into(),
+                url: "https://example.com".into(),
+            }]
+        );
+    }
+}
@@ -1,37 +1,215 @@
-use eyre::Result;
+pub mod error;
+pub mod facet;
+
+use std::{future::Future, num::NonZeroU64, path::Path};
+
+use eyre::{eyre, Result};
 
 use atrium_api::{
-    agent::atp_agent::{store::MemorySessionStore, AtpAgent},
+    agent::atp_agent::{store::MemorySessionStore, AtpAgent, AtpSession},
     app::bsky::{
-        feed::{defs, get_timeline, post, search_posts},
+        actor::{
+            self,
+            defs::{MutedWord, MutedWordsPrefData, PreferencesItem},
+            get_preferences, get_profile, put_preferences,
+        },
+        embed::{self, defs::AspectRatioData, images},
+        feed::{
+            defs, get_author_feed, get_post_thread, get_posts, get_timeline, post, search_posts,
+        },
+        graph::{get_blocks, get_followers, get_follows, get_mutes, mute_actor, unmute_actor},
         notification,
     },
     com::atproto::{repo, server},
     record::KnownRecord,
-    types::string::{AtIdentifier, Cid, Datetime, Did, Handle, Nsid},
+    types::{
+        string::{AtIdentifier, Cid, Datetime, Did, Handle, Nsid},
+        TryFromUnknown, Union,
+    },
 };
+use atrium_xrpc::http::StatusCode;
 use atrium_xrpc_client::reqwest::ReqwestClient;
 
-use bsky_sdk::BskyAgent;
+use self::error::ApiError;
+
+use crate::app::attachment::ImageData;
+
+use bsky_sdk::{
+    agent::config::{Config, FileStore},
+    BskyAgent,
+};
 
 pub type Agent = AtpAgent<MemorySessionStore, ReqwestClient>;
 
-pub async fn session(
-    agent: &BskyAgent,
-    email: String,
+/// Builds an agent for `identifier` (an email, handle or DID) on `service`, resuming the session
+/// stored at `session_path` when possible and falling back to a password login when there is
+/// none or it has been rejected.
+///
+/// `auth_factor_token` is the sign-in code emailed to accounts with two-factor authentication,
+/// see [`is_auth_factor_required`].
+pub async fn agent_with_session(
+    identifier: String,
     password: String,
-) -> Result<server::create_session::Output> {
-    let session = agent.login(email, password).await?;
-    Ok(session)
-}
+    service: Option<String>,
+    auth_factor_token: Option<String>,
+    session_path: &Path,
+) -> Result<BskyAgent> {
+    if let Some(agent) = resume_agent(&identifier, session_path).await {
+        return Ok(agent);
+    }
 
-pub async fn agent_with_session(email: String, password: String) -> Result<BskyAgent> {
-    let agent = BskyAgent::builder().build().await?;
-    let session = agent.login(email, password).await?;
-    agent.resume_session(session).await?;
+    let mut config = Config::default();
+    if let Some(service) = service {
+        config.endpoint = service;
+    }
+    let agent = BskyAgent::builder().config(config).build().await?;
+    match auth_factor_token {
+        Some(auth_factor_token) => {
+            // `login` has no way to pass the sign-in code, so create the session by hand.
+            let session = agent
+                .api
+                .com
+                .atproto
+                .server
+                .create_session(
+                    server::create_session::InputData {
+                        allow_takendown: None,
+                        auth_factor_token: Some(auth_factor_token),
+                        identifier,
+                        password,
+                    }
+                    .into(),
+                )
+                .await
+                .map_err(ApiError::from)?;
+            agent
+                .resume_session(session)
+                .await
+                .map_err(ApiError::from)?;
+        }
+        None => {
+            agent
+                .login(identifier, password)
+                .await
+                .map_err(ApiError::from)?;
+        }
+    }
+    save_session(&agent, session_path).await?;
     Ok(agent)
 }
 
+/// Whether a login failed because the account needs the sign-in code sent by email.
+pub fn is_auth_factor_required(e: &eyre::Report) -> bool {
+    api_error(e).is_some_and(|e| e.name.as_deref() == Some("AuthFactorTokenRequired"))
+}
+
+async fn resume_agent(identifier: &str, session_path: &Path) -> Option<BskyAgent> {
+    let config = Config::load(&FileStore::new(session_path)).await.ok()?;
+    if !is_session_for(config.session.as_ref()?, identifier) {
+        return None;
+    }
+    // `build` resumes the session, refreshing the access token if needed.
+    let agent = BskyAgent::builder().config(config).build().await.ok()?;
+    save_session(&agent, session_path).await.ok()?;
+    Some(agent)
+}
+
+fn is_session_for(session: &AtpSession, identifier: &str) -> bool {
+    session
+        .email
+        .as_deref()
+        .is_some_and(|email| email.eq_ignore_ascii_case(identifier))
+        || session.handle.as_str().eq_ignore_ascii_case(identifier)
+        || session.did.as_str() == identifier
+}
+
+pub async fn save_session(agent: &BskyAgent, session_path: &Path) -> Result<()> {
+    if let Some(prefix) = session_path.parent() {
+        std::fs::create_dir_all(prefix)?;
+    }
+    agent
+        .to_config()
+        .await
+        .save(&FileStore::new(session_path))
+        .await?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(session_path, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    Ok(())
+}
+
+/// Runs `f` and, if it failed because the session is no longer valid, re-authenticates
+/// `agent` and runs `f` once more.
+pub async fn with_reauth<T, F, Fut>(
+    agent: &BskyAgent,
+    identifier: &str,
+    password: &str,
+    session_path: &Path,
+    f: F,
+) -> Result<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    match f().await {
+        Err(e) if is_auth_error(&e) => {
+            reauthenticate(agent, identifier, password, session_path).await?;
+            f().await
+        }
+        result => result,
+    }
+}
+
+/// The API error `e` was caused by, if any.
+fn api_error(e: &eyre::Report) -> Option<&ApiError> {
+    e.chain().find_map(|e| e.downcast_ref::<ApiError>())
+}
+
+pub fn is_auth_error(e: &eyre::Report) -> bool {
+    api_error(e).is_some_and(|e| {
+        matches!(
+            e.name.as_deref(),
+            Some("ExpiredToken" | "InvalidToken" | "AuthMissing" | "AuthenticationRequired")
+        )
+    })
+}
+
+pub fn is_rate_limited(e: &eyre::Report) -> bool {
+    api_error(e).is_some_and(|e| {
+        e.status == Some(StatusCode::TOO_MANY_REQUESTS)
+            || e.name.as_deref() == Some("RateLimitExceeded")
+    })
+}
+
+pub fn is_network_error(e: &eyre::Report) -> bool {
+    api_error(e).is_some_and(|e| e.network)
+}
+
+async fn reauthenticate(
+    agent: &BskyAgent,
+    identifier: &str,
+    password: &str,
+    session_path: &Path,
+) -> Result<()> {
+    // Resuming goes through `getSession`, which makes the agent refresh an expired access token.
+    // The session is cleared when the refresh token is rejected too, so log in again then.
+    let resumed = match agent.get_session().await {
+        Some(session) => agent.resume_session(session).await.is_ok(),
+        None => false,
+    };
+    if !resumed {
+        agent
+            .login(identifier, password)
+            .await
+            .map_err(ApiError::from)?;
+    }
+    save_session(agent, session_path).await
+}
+
 pub async fn timeline(agent: &BskyAgent, cursor: Option<String>) -> Result<get_timeline::Output> {
     let timeline = agent
         .api
@@ -46,7 +224,8 @@ pub async fn timeline(agent: &BskyAgent, cursor: Option<String>) -> Result<get_t
             }
             .into(),
         )
-        .await?;
+        .await
+        .map_err(ApiError::from)?;
 
     Ok(timeline)
 }
@@ -78,32 +257,363 @@ pub async fn search(
             }
             .into(),
         )
-        .await?;
+        .await
+        .map_err(ApiError::from)?;
 
     Ok(search_result)
 }
 
+pub async fn post_thread(agent: &BskyAgent, uri: String) -> Result<get_post_thread::Output> {
+    let thread = agent
+        .api
+        .app
+        .bsky
+        .feed
+        .get_post_thread(
+            get_post_thread::ParametersData {
+                depth: None,
+                parent_height: None,
+                uri: uri.clone(),
+            }
+            .into(),
+        )
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(thread)
+}
+
+/// Fetches the profile of `actor`, a handle or DID.
+pub async fn profile(
+    agent: &BskyAgent,
+    actor: String,
+) -> Result<actor::defs::ProfileViewDetailedData> {
+    let profile = agent
+        .api
+        .app
+        .bsky
+        .actor
+        .get_profile(
+            get_profile::ParametersData {
+                actor: at_identifier(&actor)?,
+            }
+            .into(),
+        )
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(profile.data)
+}
+
+/// Fetches a page of the posts and reposts of `actor`, a handle or DID.
+pub async fn author_feed(
+    agent: &BskyAgent,
+    actor: String,
+    cursor: Option<String>,
+) -> Result<get_author_feed::Output> {
+    let feed = agent
+        .api
+        .app
+        .bsky
+        .feed
+        .get_author_feed(
+            get_author_feed::ParametersData {
+                actor: at_identifier(&actor)?,
+                cursor,
+                filter: None,
+                include_pins: None,
+                limit: None,
+            }
+            .into(),
+        )
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(feed)
+}
+
+/// Fetches a page of the accounts following `actor`, a handle or DID.
+pub async fn followers(
+    agent: &BskyAgent,
+    actor: String,
+    cursor: Option<String>,
+) -> Result<get_followers::Output> {
+    let followers = agent
+        .api
+        .app
+        .bsky
+        .graph
+        .get_followers(
+            get_followers::ParametersData {
+                actor: at_identifier(&actor)?,
+                cursor,
+                limit: None,
+            }
+            .into(),
+        )
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(followers)
+}
+
+/// Fetches a page of the accounts `actor`, a handle or DID, follows.
+pub async fn follows(
+    agent: &BskyAgent,
+    actor: String,
+    cursor: Option<String>,
+) -> Result<get_follows::Output> {
+    let follows = agent
+        .api
+        .app
+        .bsky
+        .graph
+        .get_follows(
+            get_follows::ParametersData {
+                actor: at_identifier(&actor)?,
+                cursor,
+                limit: None,
+            }
+            .into(),
+        )
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(follows)
+}
+
+/// Fetches a page of the accounts we muted.
+pub async fn mutes(agent: &BskyAgent, cursor: Option<String>) -> Result<get_mutes::Output> {
+    let mutes = agent
+        .api
+        .app
+        .bsky
+        .graph
+        .get_mutes(
+            get_mutes::ParametersData {
+                cursor,
+                limit: None,
+            }
+            .into(),
+        )
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(mutes)
+}
+
+/// Fetches a page of the accounts we blocked.
+pub async fn blocks(agent: &BskyAgent, cursor: Option<String>) -> Result<get_blocks::Output> {
+    let blocks = agent
+        .api
+        .app
+        .bsky
+        .graph
+        .get_blocks(
+            get_blocks::ParametersData {
+                cursor,
+                limit: None,
+            }
+            .into(),
+        )
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(blocks)
+}
+
+/// Fetches the words we muted, from the `mutedWordsPref` preference.
+pub async fn muted_words(agent: &BskyAgent) -> Result<Vec<MutedWord>> {
+    let preferences = preferences(agent).await?;
+    let muted_words = preferences
+        .into_iter()
+        .find_map(|preference| match preference {
+            Union::Refs(PreferencesItem::MutedWordsPref(pref)) => Some(pref.data.items),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    Ok(muted_words)
+}
+
+/// Replaces the words we muted, leaving the other preferences as they are.
+pub async fn set_muted_words(agent: &BskyAgent, muted_words: Vec<MutedWord>) -> Result<()> {
+    let mut preferences = preferences(agent).await?;
+    preferences.retain(|preference| {
+        !matches!(preference, Union::Refs(PreferencesItem::MutedWordsPref(_)))
+    });
+    preferences.push(Union::Refs(PreferencesItem::MutedWordsPref(Box::new(
+        MutedWordsPrefData { items: muted_words }.into(),
+    ))));
+    agent
+        .api
+        .app
+        .bsky
+        .actor
+        .put_preferences(put_preferences::InputData { preferences }.into())
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(())
+}
+
+async fn preferences(agent: &BskyAgent) -> Result<actor::defs::Preferences> {
+    let output = agent
+        .api
+        .app
+        .bsky
+        .actor
+        .get_preferences(get_preferences::ParametersData {}.into())
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(output.data.preferences)
+}
+
+/// Parses a handle (with or without the leading `@`) or DID typed by the user.
+fn at_identifier(actor: &str) -> Result<AtIdentifier> {
+    actor
+        .trim()
+        .trim_start_matches('@')
+        .parse()
+        .map_err(|_| eyre!("{actor} is not a valid handle or DID"))
+}
+
+/// Fetches the post at `uri`.
+pub async fn post(agent: &BskyAgent, uri: String) -> Result<defs::PostViewData> {
+    let output = agent
+        .api
+        .app
+        .bsky
+        .feed
+        .get_posts(get_posts::ParametersData { uris: vec![uri] }.into())
+        .await
+        .map_err(ApiError::from)?;
+
+    output
+        .data
+        .posts
+        .into_iter()
+        .next()
+        .map(|post| post.data)
+        .ok_or_else(|| eyre!("the post was deleted"))
+}
+
+/// Builds the reply reference for replying to `post`: the parent is `post` itself and the root is
+/// the root of the thread `post` is part of, or `post` when it is not a reply.
+pub fn reply_ref(post: &defs::PostViewData) -> post::ReplyRef {
+    let parent: repo::strong_ref::Main = repo::strong_ref::MainData {
+        cid: post.cid.clone(),
+        uri: post.uri.clone(),
+    }
+    .into();
+    let root = post::Record::try_from_unknown(post.record.clone())
+        .ok()
+        .and_then(|record| record.data.reply)
+        .map(|reply| reply.data.root)
+        .unwrap_or_else(|| parent.clone());
+
+    post::ReplyRefData { root, parent }.into()
+}
+
+/// Builds the embed for a post quoting `post`, with `media` (the images attached to the quoting
+/// post) alongside it if there is any.
+pub fn quote_embed(
+    post: &defs::PostViewData,
+    media: Option<Union<post::RecordEmbedRefs>>,
+) -> Union<post::RecordEmbedRefs> {
+    let record: embed::record::Main = embed::record::MainData {
+        record: repo::strong_ref::MainData {
+            cid: post.cid.clone(),
+            uri: post.uri.clone(),
+        }
+        .into(),
+    }
+    .into();
+
+    let media = match media {
+        Some(Union::Refs(post::RecordEmbedRefs::AppBskyEmbedImagesMain(images))) => {
+            Some(embed::record_with_media::MainMediaRefs::AppBskyEmbedImagesMain(images))
+        }
+        _ => None,
+    };
+    match media {
+        Some(media) => Union::Refs(post::RecordEmbedRefs::AppBskyEmbedRecordWithMediaMain(
+            Box::new(
+                embed::record_with_media::MainData {
+                    media: Union::Refs(media),
+                    record,
+                }
+                .into(),
+            ),
+        )),
+        None => Union::Refs(post::RecordEmbedRefs::AppBskyEmbedRecordMain(Box::new(
+            record,
+        ))),
+    }
+}
+
+/// Creates a post and returns a strong reference to it, for replying to it.
 pub async fn send_post(
     agent: &BskyAgent,
     _did: Did,
     text: String,
     reply: Option<post::ReplyRef>,
-) -> Result<()> {
-    agent
+    embed: Option<Union<post::RecordEmbedRefs>>,
+) -> Result<repo::strong_ref::Main> {
+    let output = agent
         .create_record(post::RecordData {
             created_at: Datetime::now(),
-            embed: None,
+            embed,
             entities: None,
-            facets: None,
+            facets: Some(facet::facets(agent, &text).await).filter(|f| !f.is_empty()),
             langs: None,
             labels: None,
             tags: None,
             reply,
             text,
         })
-        .await?;
+        .await
+        .map_err(ApiError::from)?;
 
-    Ok(())
+    Ok(repo::strong_ref::MainData {
+        cid: output.data.cid,
+        uri: output.data.uri,
+    }
+    .into())
+}
+
+/// Uploads `images` (with their alt text) and builds an `app.bsky.embed.images` embed for them.
+pub async fn images_embed(
+    agent: &BskyAgent,
+    images: Vec<(ImageData, String)>,
+) -> Result<Union<post::RecordEmbedRefs>> {
+    let mut embedded = Vec::new();
+    for (image, alt) in images {
+        let aspect_ratio = NonZeroU64::new(image.width.into())
+            .zip(NonZeroU64::new(image.height.into()))
+            .map(|(width, height)| AspectRatioData { width, height }.into());
+        let output = agent
+            .api
+            .com
+            .atproto
+            .repo
+            .upload_blob(image.bytes)
+            .await
+            .map_err(ApiError::from)?;
+        embedded.push(
+            images::ImageData {
+                alt,
+                aspect_ratio,
+                image: output.data.blob,
+            }
+            .into(),
+        );
+    }
+
+    Ok(Union::Refs(post::RecordEmbedRefs::AppBskyEmbedImagesMain(
+        Box::new(images::MainData { images: embedded }.into()),
+    )))
 }
 
 pub async fn notifications(agent: &BskyAgent) -> Result<notification::list_notifications::Output> {
@@ -122,7 +632,8 @@ pub async fn notifications(agent: &BskyAgent) -> Result<notification::list_notif
             }
             .into(),
         )
-        .await?;
+        .await
+        .map_err(ApiError::from)?;
 
     Ok(notifications)
 }
@@ -145,7 +656,8 @@ pub async fn likes(agent: &BskyAgent, did: String) -> Result<repo::list_records:
             }
             .into(),
         )
-        .await?;
+        .await
+        .map_err(ApiError::from)?;
 
     Ok(likes)
 }
@@ -168,7 +680,8 @@ pub async fn reposts(agent: &BskyAgent, did: String) -> Result<repo::list_record
             }
             .into(),
         )
-        .await?;
+        .await
+        .map_err(ApiError::from)?;
 
     Ok(reposts)
 }
@@ -198,7 +711,8 @@ pub async fn like(agent: &BskyAgent, _did: Did, cid: Cid, uri: String) -> Result
             }
             .into(),
         )))
-        .await?;
+        .await
+        .map_err(ApiError::from)?;
 
     Ok(())
 }
@@ -219,7 +733,8 @@ pub async fn unlike(agent: &Agent, did: Did, rkey: String) -> Result<()> {
             }
             .into(),
         )
-        .await?;
+        .await
+        .map_err(ApiError::from)?;
 
     Ok(())
 }
@@ -237,7 +752,8 @@ pub async fn repost(agent: &BskyAgent, _did: Did, cid: Cid, uri: String) -> Resu
             }
             .into(),
         )))
-        .await?;
+        .await
+        .map_err(ApiError::from)?;
 
     Ok(())
 }
@@ -258,7 +774,142 @@ pub async fn unrepost(agent: &BskyAgent, did: Did, rkey: String) -> Result<()> {
             }
             .into(),
         )
-        .await?;
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(())
+}
+
+pub async fn delete_post(agent: &BskyAgent, did: Did, rkey: String) -> Result<()> {
+    agent
+        .api
+        .com
+        .atproto
+        .repo
+        .delete_record(
+            repo::delete_record::InputData {
+                collection: Nsid::new("app.bsky.feed.post".to_string()).unwrap(),
+                repo: AtIdentifier::Did(did),
+                rkey,
+                swap_commit: None,
+                swap_record: None,
+            }
+            .into(),
+        )
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(())
+}
+
+/// Follows `subject`, returning the URI of the follow record.
+pub async fn follow(agent: &BskyAgent, subject: Did) -> Result<String> {
+    let output = agent
+        .create_record(KnownRecord::AppBskyGraphFollow(Box::new(
+            atrium_api::app::bsky::graph::follow::RecordData {
+                created_at: Datetime::now(),
+                subject,
+            }
+            .into(),
+        )))
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(output.data.uri)
+}
+
+pub async fn unfollow(agent: &BskyAgent, did: Did, rkey: String) -> Result<()> {
+    agent
+        .api
+        .com
+        .atproto
+        .repo
+        .delete_record(
+            repo::delete_record::InputData {
+                collection: Nsid::new("app.bsky.graph.follow".to_string()).unwrap(),
+                repo: AtIdentifier::Did(did),
+                rkey,
+                swap_commit: None,
+                swap_record: None,
+            }
+            .into(),
+        )
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(())
+}
+
+pub async fn mute(agent: &BskyAgent, subject: Did) -> Result<()> {
+    agent
+        .api
+        .app
+        .bsky
+        .graph
+        .mute_actor(
+            mute_actor::InputData {
+                actor: AtIdentifier::Did(subject),
+            }
+            .into(),
+        )
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(())
+}
+
+pub async fn unmute(agent: &BskyAgent, subject: Did) -> Result<()> {
+    agent
+        .api
+        .app
+        .bsky
+        .graph
+        .unmute_actor(
+            unmute_actor::InputData {
+                actor: AtIdentifier::Did(subject),
+            }
+            .into(),
+        )
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(())
+}
+
+/// Blocks `subject`, returning the URI of the block record.
+pub async fn block(agent: &BskyAgent, subject: Did) -> Result<String> {
+    let output = agent
+        .create_record(KnownRecord::AppBskyGraphBlock(Box::new(
+            atrium_api::app::bsky::graph::block::RecordData {
+                created_at: Datetime::now(),
+                subject,
+            }
+            .into(),
+        )))
+        .await
+        .map_err(ApiError::from)?;
+
+    Ok(output.data.uri)
+}
+
+pub async fn unblock(agent: &BskyAgent, did: Did, rkey: String) -> Result<()> {
+    agent
+        .api
+        .com
+        .atproto
+        .repo
+        .delete_record(
+            repo::delete_record::InputData {
+                collection: Nsid::new("app.bsky.graph.block".to_string()).unwrap(),
+                repo: AtIdentifier::Did(did),
+                rkey,
+                swap_commit: None,
+                swap_record: None,
+            }
+            .into(),
+        )
+        .await
+        .map_err(ApiError::from)?;
 
     Ok(())
 }
@@ -276,7 +927,7 @@ pub async fn toggle_repost(agent: &BskyAgent, did: Did, feed: defs::FeedViewPost
 }
 
 pub fn get_url(handle: Handle, uri: String) -> Option<String> {
-    if let Some(id) = uri.split('/').last() {
+    if let Some(id) = uri.split('/').next_back() {
         let handle = handle.to_string();
         Some(format!("https://bsky.app/profile/{handle}/post/{id}"))
     } else {
@@ -285,7 +936,7 @@ pub fn get_url(handle: Handle, uri: String) -> Option<String> {
 }
 
 pub fn uri_to_rkey(uri: String) -> Option<String> {
-    uri.split('/').last().map(|s| s.to_string())
+    uri.split('/').next_back().map(|s| s.to_string())
 }
 
 pub async fn toggle_like_post_view(
@@ -319,3 +970,130 @@ pub async fn toggle_repost_post_view(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOT_URI: &str = "at://did:plc:root/app.bsky.feed.post/root";
+    const ROOT_CID: &str = "bafyreidfayvfuwqa7qlnopdjiqrxzs6blmoeu4rujcjtnci5beludirz2a";
+    const PARENT_URI: &str = "at://did:plc:parent/app.bsky.feed.post/parent";
+    const PARENT_CID: &str = "bafyreie5737gdxlw5i64vzichcalba3z2v5n6icifvx5xytvske7mr3hpm";
+    const POST_URI: &str = "at://did:plc:author/app.bsky.feed.post/post";
+    const POST_CID: &str = "bafyreib2rxk3rh6kzwq3y4hxofikpukm5bqdqnz2m4fh6xo7v6rc2qhmvu";
+
+    fn post_view(
+        uri: &str,
+        cid: &str,
+        reply: Option<(&str, &str, &str, &str)>,
+    ) -> defs::PostViewData {
+        let mut record = serde_json::json!({
+            "$type": "app.bsky.feed.post",
+            "text": "hello",
+            "createdAt": "2024-01-01T00:00:00.000Z",
+        });
+        if let Some((root_uri, root_cid, parent_uri, parent_cid)) = reply {
+            record["reply"] = serde_json::json!({
+                "root": { "uri": root_uri, "cid": root_cid },
+                "parent": { "uri": parent_uri, "cid": parent_cid },
+            });
+        }
+        serde_json::from_value(serde_json::json!({
+            "uri": uri,
+            "cid": cid,
+            "author": { "did": "did:plc:author", "handle": "author.bsky.social" },
+            "record": record,
+            "indexedAt": "2024-01-01T00:00:00.000Z",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn reply_to_top_level_post_uses_it_as_root_and_parent() {
+        let post = post_view(POST_URI, POST_CID, None);
+
+        let reply = reply_ref(&post);
+
+        assert_eq!(reply.root.uri, POST_URI);
+        assert_eq!(reply.root.cid.as_ref().to_string(), POST_CID);
+        assert_eq!(reply.parent.uri, POST_URI);
+        assert_eq!(reply.parent.cid.as_ref().to_string(), POST_CID);
+    }
+
+    #[test]
+    fn reply_to_direct_reply_keeps_thread_root() {
+        let post = post_view(
+            POST_URI,
+            POST_CID,
+            Some((ROOT_URI, ROOT_CID, ROOT_URI, ROOT_CID)),
+        );
+
+        let reply = reply_ref(&post);
+
+        assert_eq!(reply.root.uri, ROOT_URI);
+        assert_eq!(reply.root.cid.as_ref().to_string(), ROOT_CID);
+        assert_eq!(reply.parent.uri, POST_URI);
+        assert_eq!(reply.parent.cid.as_ref().to_string(), POST_CID);
+    }
+
+    #[test]
+    fn reply_to_nested_reply_keeps_thread_root() {
+        let post = post_view(
+            POST_URI,
+            POST_CID,
+            Some((ROOT_URI, ROOT_CID, PARENT_URI, PARENT_CID)),
+        );
+
+        let reply = reply_ref(&post);
+
+        assert_eq!(reply.root.uri, ROOT_URI);
+        assert_eq!(reply.root.cid.as_ref().to_string(), ROOT_CID);
+        assert_eq!(reply.parent.uri, POST_URI);
+        assert_eq!(reply.parent.cid.as_ref().to_string(), POST_CID);
+    }
+
+    #[test]
+    fn quote_embeds_a_strong_ref_to_the_post() {
+        let post = post_view(POST_URI, POST_CID, None);
+
+        let Union::Refs(post::RecordEmbedRefs::AppBskyEmbedRecordMain(embed)) =
+            quote_embed(&post, None)
+        else {
+            panic!("expected a record embed");
+        };
+        assert_eq!(embed.record.uri, POST_URI);
+        assert_eq!(embed.record.cid.as_ref().to_string(), POST_CID);
+    }
+
+    #[test]
+    fn quote_with_images_embeds_record_with_media() {
+        let post = post_view(POST_URI, POST_CID, None);
+        let images = Union::Refs(post::RecordEmbedRefs::AppBskyEmbedImagesMain(Box::new(
+            images::MainData { images: vec![] }.into(),
+        )));
+
+        let Union::Refs(post::RecordEmbedRefs::AppBskyEmbedRecordWithMediaMain(embed)) =
+            quote_embed(&post, Some(images))
+        else {
+            panic!("expected a record with media embed");
+        };
+        assert_eq!(embed.record.record.uri, POST_URI);
+        assert!(matches!(
+            embed.media,
+            Union::Refs(embed::record_with_media::MainMediaRefs::AppBskyEmbedImagesMain(_))
+        ));
+    }
+
+    #[test]
+    fn typed_handles_and_dids_are_accepted() {
+        assert_eq!(
+            at_identifier(" @alice.bsky.social").unwrap(),
+            AtIdentifier::Handle("alice.bsky.social".parse().unwrap())
+        );
+        assert_eq!(
+            at_identifier("did:plc:author").unwrap(),
+            AtIdentifier::Did("did:plc:author".parse().unwrap())
+        );
+        assert!(at_identifier("not a handle").is_err());
+    }
+}
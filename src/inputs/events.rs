@@ -6,27 +6,56 @@ use std::{
     time::Duration,
 };
 
+use tokio::sync::{mpsc, oneshot};
+
 use super::{key::Key, InputEvent};
 
+/// Tells the task reading the terminal to stop or start reading.
+enum Capture {
+    /// Stops reading, answering on the sender once nothing is being read anymore.
+    Pause(oneshot::Sender<()>),
+    Resume,
+}
+
 pub struct Events {
-    rx: tokio::sync::mpsc::Receiver<InputEvent>,
-    _tx: tokio::sync::mpsc::Sender<InputEvent>,
+    rx: mpsc::Receiver<InputEvent>,
+    _tx: mpsc::Sender<InputEvent>,
     stop_capture: Arc<AtomicBool>,
+    capture_tx: mpsc::UnboundedSender<Capture>,
 }
 
 impl Events {
     pub fn new(tick_rate: Duration) -> Events {
-        let (tx, rx) = tokio::sync::mpsc::channel(100);
+        let (tx, rx) = mpsc::channel(100);
         let stop_capture = Arc::new(AtomicBool::new(false));
+        let (capture_tx, mut capture_rx) = mpsc::unbounded_channel();
 
         let event_tx = tx.clone();
         let event_stop_capture = stop_capture.clone();
         tokio::spawn(async move {
             loop {
+                if let Ok(Capture::Pause(paused)) = capture_rx.try_recv() {
+                    let _ = paused.send(());
+                    loop {
+                        match capture_rx.recv().await {
+                            Some(Capture::Resume) => break,
+                            Some(Capture::Pause(paused)) => {
+                                let _ = paused.send(());
+                            }
+                            None => return,
+                        }
+                    }
+                }
                 if crossterm::event::poll(tick_rate).unwrap() {
-                    if let crossterm::event::Event::Key(key) = crossterm::event::read().unwrap() {
-                        let key = Key::from(key);
-                        let _ = event_tx.send(InputEvent::Input(key)).await;
+                    match crossterm::event::read().unwrap() {
+                        crossterm::event::Event::Key(key) => {
+                            let key = Key::from(key);
+                            let _ = event_tx.send(InputEvent::Input(key)).await;
+                        }
+                        crossterm::event::Event::Paste(text) => {
+                            let _ = event_tx.send(InputEvent::Paste(text)).await;
+                        }
+                        _ => {}
                     }
                 }
                 let _ = event_tx.send(InputEvent::Tick).await;
@@ -40,6 +69,7 @@ impl Events {
             rx,
             _tx: tx,
             stop_capture,
+            capture_tx,
         }
     }
 
@@ -52,4 +82,20 @@ impl Events {
     pub fn close(&mut self) {
         self.stop_capture.store(true, Ordering::Relaxed)
     }
+
+    /// Stops reading the terminal so that another program can use it.
+    ///
+    /// Returns once a read that was already waiting is over, so nothing is taken from the input
+    /// meant for that program.
+    pub async fn pause(&mut self) {
+        let (paused_tx, paused_rx) = oneshot::channel();
+        if self.capture_tx.send(Capture::Pause(paused_tx)).is_ok() {
+            let _ = paused_rx.await;
+        }
+    }
+
+    /// Reads the terminal again after [`Events::pause`].
+    pub fn resume(&mut self) {
+        let _ = self.capture_tx.send(Capture::Resume);
+    }
 }
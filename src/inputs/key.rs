@@ -7,6 +7,12 @@ use crossterm::event;
 pub enum Key {
     /// Both Enter (or Return) and numpad Enter
     Enter,
+    /// Shift+Enter, which terminals only report with the kitty keyboard protocol
+    ShiftEnter,
+    /// Ctrl+Enter, which terminals only report with the kitty keyboard protocol
+    CtrlEnter,
+    /// Alt+Enter (or Option+Enter)
+    AltEnter,
     /// Tabulation key
     Tab,
     /// Backspace key
@@ -183,6 +189,24 @@ impl From<event::KeyEvent> for Key {
                 kind: event::KeyEventKind::Press,
                 ..
             } => Key::from_f(n),
+            event::KeyEvent {
+                code: event::KeyCode::Enter,
+                kind: event::KeyEventKind::Press,
+                modifiers: event::KeyModifiers::SHIFT,
+                ..
+            } => Key::ShiftEnter,
+            event::KeyEvent {
+                code: event::KeyCode::Enter,
+                kind: event::KeyEventKind::Press,
+                modifiers: event::KeyModifiers::CONTROL,
+                ..
+            } => Key::CtrlEnter,
+            event::KeyEvent {
+                code: event::KeyCode::Enter,
+                kind: event::KeyEventKind::Press,
+                modifiers: event::KeyModifiers::ALT,
+                ..
+            } => Key::AltEnter,
             event::KeyEvent {
                 code: event::KeyCode::Enter,
                 kind: event::KeyEventKind::Press,
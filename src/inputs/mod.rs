@@ -5,5 +5,7 @@ use self::key::Key;
 
 pub enum InputEvent {
     Input(Key),
+    /// Text pasted into the terminal, including the paths of files dropped onto it.
+    Paste(String),
     Tick,
 }
@@ -1,141 +1,396 @@
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 
-use atrium_api::{app::bsky::feed::post::ReplyRefData, com::atproto::repo::strong_ref};
-use eyre::Result;
-use tui_input::Input;
+use atrium_api::{
+    app::bsky::{actor::defs::MutedWord, feed::post},
+    types::{
+        string::{Did, Handle},
+        Union,
+    },
+};
+use chrono::Local;
+use eyre::{bail, eyre, Result};
 
-use super::{IoEvent, SearchEvent, TimelineEvent};
+use super::{
+    FollowsEvent, IoEvent, LoginEvent, ProfileEvent, SearchEvent, ThreadEvent, TimelineEvent,
+};
 use crate::{
-    app::{config::AppConfig, state::Mode, state::Tab, App},
+    app::{
+        attachment::{self, Attachment},
+        composer::{self, Composer, MAX_GRAPHEMES},
+        config::AppConfig,
+        drafts::{Draft, DraftKind},
+        follows::{FollowList, FollowListKind},
+        message::Message,
+        pages::Page,
+        preview::{self, Thumbnail},
+        profile::Profile,
+        state::{AppState, Mode, Moderation, Tab},
+        thread::Thread,
+        App,
+    },
     bsky,
 };
 
 pub struct IoAsyncHandler {
     app: Arc<tokio::sync::Mutex<App>>,
+    config: AppConfig,
+    /// Client for downloading thumbnails, which are served from a CDN rather than the API.
+    http: reqwest::Client,
 }
 
 impl IoAsyncHandler {
-    pub fn new(app: Arc<tokio::sync::Mutex<App>>) -> Self {
-        Self { app }
+    pub fn new(app: Arc<tokio::sync::Mutex<App>>, config: AppConfig) -> Self {
+        Self {
+            app,
+            config,
+            http: reqwest::Client::new(),
+        }
     }
 
     pub async fn handle_io_event(&mut self, io_event: IoEvent) {
-        let _ = match io_event {
+        let result = match io_event {
             IoEvent::Initialize => self.do_initialize().await,
+            IoEvent::Login(event) => self.do_login(event).await,
+            IoEvent::SwitchAccount(index) => self.do_switch_account(index).await,
             IoEvent::LoadTimeline(action) => self.do_load_timeline(action).await,
-            IoEvent::SendPost => self.do_send_post().await,
+            IoEvent::SendPost(text) => self.do_send_post(text).await,
+            IoEvent::SendThread(text, numbered) => self.do_send_thread(text, numbered).await,
             IoEvent::LoadNotifications => self.do_load_notifications().await,
             IoEvent::Like => self.do_like().await,
             IoEvent::Repost => self.do_repost().await,
-            IoEvent::Reply => self.do_reply().await,
+            IoEvent::Reply(text) => self.do_reply(text).await,
             IoEvent::Search(action) => self.do_search(action).await,
             IoEvent::SearchLike => self.do_search_like().await,
             IoEvent::SearchRepost => self.do_search_repost().await,
-            IoEvent::SearchReply => self.do_search_reply().await,
+            IoEvent::SearchReply(text) => self.do_search_reply(text).await,
+            IoEvent::LoadThread(event) => self.do_load_thread(event).await,
+            IoEvent::LoadProfile(event) => self.do_load_profile(event).await,
+            IoEvent::LoadFollows(event) => self.do_load_follows(event).await,
+            IoEvent::ViewLike => self.do_view_like().await,
+            IoEvent::ViewRepost => self.do_view_repost().await,
+            IoEvent::ViewReply(text) => self.do_view_reply(text).await,
+            IoEvent::Quote(text) => self.do_quote(text).await,
+            IoEvent::Follow(did, handle) => self.do_follow(did, handle).await,
+            IoEvent::Unfollow(did, handle, uri) => self.do_unfollow(did, handle, uri).await,
+            IoEvent::Moderate(moderation) => self.do_moderate(moderation).await,
+            IoEvent::DeletePost => self.do_delete_post().await,
+            IoEvent::SaveMutedWords(muted_words) => self.do_save_muted_words(muted_words).await,
+            IoEvent::LoadThumbnail(url) => self.do_load_thumbnail(url).await,
+            IoEvent::OpenDraft(index) => self.do_open_draft(index, false).await,
+            IoEvent::SendDraft(index) => self.do_open_draft(index, true).await,
         };
 
         let mut app = self.app.lock().await;
+        // Some handlers return early without clearing the flag, so never leave it set.
+        app.state.set_loading(false);
+        if let Err(e) = result {
+            app.state.push_message(Message::error(error_message(&e)));
+        }
         app.loaded();
     }
 
-    async fn do_initialize(&mut self) -> Result<()> {
-        {
-            let config = AppConfig::load()?;
-            let mut app = self.app.lock().await;
-            let agent =
-                bsky::agent_with_session(config.email.clone(), config.password.clone()).await?;
-            let session =
-                bsky::session(&agent, config.email.clone(), config.password.clone()).await?;
-            app.initialized(agent, session.handle.clone(), session.did.clone(), config);
+    async fn notify(&self, message: Message) {
+        let mut app = self.app.lock().await;
+        app.state.push_message(message);
+    }
+
+    /// Awaits `send`, which sends a post, keeping `draft` if it fails so that nothing written is
+    /// lost. Failing to reload afterwards does not keep it, as the post is already out.
+    async fn send<T>(
+        &self,
+        draft: Option<Draft>,
+        send: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        match send.await {
+            Ok(sent) => Ok(sent),
+            Err(e) => Err(self.keep_draft(draft, e).await),
         }
-        self.do_load_timeline(TimelineEvent::Load).await?;
+    }
 
-        Ok(())
+    /// Keeps `draft` after sending it failed with `e`, and returns the error to show.
+    async fn keep_draft(&self, draft: Option<Draft>, e: eyre::Report) -> eyre::Report {
+        match draft {
+            Some(draft) => {
+                self.app.lock().await.save_draft(draft);
+                eyre!("{} (saved as a draft)", error_message(&e))
+            }
+            None => e,
+        }
     }
 
-    async fn do_load_timeline(&mut self, event: TimelineEvent) -> Result<()> {
-        let current_cursor_index = {
+    /// Calls the API through `f`, logging in again and retrying once if the session expired.
+    ///
+    /// Hitting the rate limit is returned as an error rather than waited out, as waiting would
+    /// hold up every event queued behind this one.
+    async fn call<T, F, Fut>(&self, f: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let (agent, account) = {
             let app = self.app.lock().await;
-            app.state.get_tl_current_cursor_index()
+            let agent = app
+                .state
+                .get_agent()
+                .ok_or_else(|| eyre!("not logged in"))?;
+            (agent, app.state.get_account())
         };
+        let session_path = AppConfig::session_path(&account);
+        bsky::with_reauth(
+            &agent,
+            &account.identifier,
+            &account.password,
+            &session_path,
+            f,
+        )
+        .await
+    }
 
-        if current_cursor_index == 0 && event == TimelineEvent::Prev {
-            return Ok(());
+    async fn do_initialize(&mut self) -> Result<()> {
+        if let Err(e) = self.login(0, None).await {
+            self.show_login(0, None, &e).await;
+            return Err(e);
+        }
+        self.load_muted_words().await;
+        if let Err(e) = self.do_load_timeline(TimelineEvent::Load).await {
+            // The splash screen waits for the timeline, so go to the login screen instead of
+            // leaving it up; logging in from there loads the timeline again.
+            self.show_login(0, None, &e).await;
+            return Err(e);
         }
 
+        Ok(())
+    }
+
+    async fn do_login(&mut self, event: LoginEvent) -> Result<()> {
+        let previous_account_index = {
+            let mut app = self.app.lock().await;
+            app.state.set_loading(true);
+            app.state.get_previous_account_index()
+        };
+        self.config
+            .set_credentials(event.account_index, event.identifier, event.password);
+        if let Err(e) = self
+            .login(event.account_index, event.auth_factor_token)
+            .await
         {
+            self.show_login(event.account_index, previous_account_index, &e)
+                .await;
+            return Err(e);
+        }
+        self.load_muted_words().await;
+        self.do_load_timeline(TimelineEvent::Load).await?;
+
+        Ok(())
+    }
+
+    async fn do_switch_account(&mut self, index: usize) -> Result<()> {
+        let previous_account_index = {
             let mut app = self.app.lock().await;
+            if app.restore_account(index) {
+                return Ok(());
+            }
             app.state.set_loading(true);
+            app.state.get_account_index()
+        };
+        if let Err(e) = self.login(index, None).await {
+            self.show_login(index, Some(previous_account_index), &e)
+                .await;
+            return Err(e);
         }
+        self.load_muted_words().await;
+        self.do_load_timeline(TimelineEvent::Load).await?;
+
+        Ok(())
+    }
+
+    /// Replaces the current screen with the login screen for `account_index`, showing why
+    /// logging in failed.
+    async fn show_login(
+        &mut self,
+        account_index: usize,
+        previous_account_index: Option<usize>,
+        e: &eyre::Report,
+    ) {
+        let account = self
+            .config
+            .accounts()
+            .get(account_index)
+            .cloned()
+            .unwrap_or_default();
+        let auth_factor_required = bsky::is_auth_factor_required(e);
+        let error = if auth_factor_required {
+            "A sign-in code has been sent to your email. Enter it below.".to_string()
+        } else {
+            format!("{e:#}")
+        };
+        let state = AppState::login(
+            account_index,
+            previous_account_index,
+            account.identifier,
+            account.password,
+            auth_factor_required,
+            Some(error),
+        );
+        let mut app = self.app.lock().await;
+        app.show_login(state);
+    }
 
+    async fn login(
+        &mut self,
+        account_index: usize,
+        auth_factor_token: Option<String>,
+    ) -> Result<()> {
+        let config = self.config.clone();
+        let account = config
+            .accounts()
+            .get(account_index)
+            .cloned()
+            .ok_or_else(|| eyre::eyre!("identifier (email, handle or DID) is required"))?;
+        account.check_required_fields()?;
+        let agent = bsky::agent_with_session(
+            account.identifier.clone(),
+            account.password.clone(),
+            account.service.clone(),
+            auth_factor_token,
+            &AppConfig::session_path(&account),
+        )
+        .await?;
+        let session = agent
+            .get_session()
+            .await
+            .ok_or_else(|| eyre::eyre!("session not found"))?;
+        let mut app = self.app.lock().await;
+        app.initialized(
+            agent,
+            session.handle.clone(),
+            session.did.clone(),
+            config,
+            account_index,
+        );
+
+        Ok(())
+    }
+
+    /// Loads the muted words of the account that just logged in. Posts are shown unfiltered if
+    /// this fails, so it does not stop the timeline from loading.
+    async fn load_muted_words(&mut self) {
         let agent = {
             let app = self.app.lock().await;
             app.state.get_agent().unwrap()
         };
-        let cursor = match event {
-            TimelineEvent::Load => None,
-            TimelineEvent::Next => {
-                let app = self.app.lock().await;
-                app.state.get_next_cursor()
-            }
-            TimelineEvent::Prev => {
-                let app = self.app.lock().await;
-                app.state.get_prev_cursor()
+        match self.call(|| bsky::muted_words(&agent)).await {
+            Ok(muted_words) => {
+                let mut app = self.app.lock().await;
+                app.state.set_muted_words(muted_words);
+                app.state.update_hidden_posts();
             }
-            TimelineEvent::Reload => {
-                let app = self.app.lock().await;
-                app.state.get_current_cursor()
+            Err(e) => {
+                self.notify(Message::error(format!(
+                    "Could not load muted words: {}",
+                    error_message(&e)
+                )))
+                .await
             }
+        }
+    }
+
+    async fn do_save_muted_words(&mut self, muted_words: Vec<MutedWord>) -> Result<()> {
+        let agent = {
+            let mut app = self.app.lock().await;
+            app.state.set_loading(true);
+            app.state.get_agent().unwrap()
         };
-        let current_cursor_index = {
+        self.call(|| bsky::set_muted_words(&agent, muted_words.clone()))
+            .await?;
+
+        let mut app = self.app.lock().await;
+        app.state.set_muted_words(muted_words);
+        app.state.update_hidden_posts();
+        app.state.push_message(Message::info("Muted words saved"));
+        app.state.set_loading(false);
+
+        Ok(())
+    }
+
+    async fn do_load_timeline(&mut self, event: TimelineEvent) -> Result<()> {
+        let (agent, pages) = {
             let app = self.app.lock().await;
-            app.state.get_tl_current_cursor_index()
+            (
+                app.state.get_agent().unwrap(),
+                app.state.get_timeline_pages(),
+            )
+        };
+        let page = match event {
+            TimelineEvent::Load => Page::FIRST,
+            TimelineEvent::Next => match pages.next() {
+                Some(page) => page,
+                None => return Ok(()),
+            },
+            TimelineEvent::Prev => match pages.prev() {
+                Some(page) => page,
+                None => return Ok(()),
+            },
+            TimelineEvent::Reload => pages.current(),
         };
-
-        if event == TimelineEvent::Prev && current_cursor_index == 0 {
-            return Ok(());
-        }
 
         {
-            let timeline = bsky::timeline(&agent, cursor).await?;
             let mut app = self.app.lock().await;
-            app.state.set_timeline(Some(timeline.feed.clone()));
+            app.state.set_loading(true);
+        }
 
-            match event {
-                TimelineEvent::Load => {
-                    let mut cursors = app.state.get_cursors().clone();
-                    cursors.push(timeline.cursor.clone());
-                    app.state.set_cursors(cursors);
-                }
-                TimelineEvent::Next => {
-                    let mut cursors = app.state.get_cursors().clone();
-                    cursors.push(timeline.cursor.clone());
-                    app.state.set_cursors(cursors);
-                    app.state
-                        .set_tl_current_cursor_index(current_cursor_index + 1);
-                }
-                TimelineEvent::Prev => {
-                    if current_cursor_index == 0 {
-                        return Ok(());
-                    }
-                    app.state
-                        .set_tl_current_cursor_index(current_cursor_index - 1);
-                }
-                _ => (),
-            }
+        let timeline = self
+            .call(|| bsky::timeline(&agent, page.cursor.clone()))
+            .await?;
+        let mut app = self.app.lock().await;
+        app.state.set_timeline(Some(timeline.feed.clone()));
+        app.state.update_hidden_posts();
+        let mut pages = app.state.get_timeline_pages();
+        pages.set_page(&page, timeline.cursor.clone());
+        app.state.set_timeline_pages(pages);
+        app.state.move_tl_scroll_top();
+        app.state.set_loading(false);
 
-            app.state.move_tl_scroll_top();
-        }
+        Ok(())
+    }
 
+    async fn do_send_post(&mut self, text: String) -> Result<()> {
+        let agent = {
+            let app = self.app.lock().await;
+            app.state.get_agent().unwrap()
+        };
+        let did = {
+            let app = self.app.lock().await;
+            app.state.get_did()
+        };
+        let (draft, attachments) = {
+            let mut app = self.app.lock().await;
+            (
+                app.state.get_draft().map(|draft| Draft {
+                    text: text.clone(),
+                    ..draft
+                }),
+                app.state.take_attachments(),
+            )
+        };
         {
             let mut app = self.app.lock().await;
-            app.state.set_loading(false);
+            app.state.set_mode(Mode::Normal);
+            app.state.clear_composer();
         }
+        self.send(draft, async {
+            let embed = self.attachments_embed(attachments).await?;
+            self.call(|| bsky::send_post(&agent, did.clone(), text.clone(), None, embed.clone()))
+                .await
+        })
+        .await?;
+        self.notify(Message::info("Post sent")).await;
+        self.do_load_timeline(TimelineEvent::Load).await?;
 
         Ok(())
     }
 
-    async fn do_send_post(&mut self) -> Result<()> {
+    async fn do_send_thread(&mut self, text: String, numbered: bool) -> Result<()> {
         let agent = {
             let app = self.app.lock().await;
             app.state.get_agent().unwrap()
@@ -144,21 +399,163 @@ impl IoAsyncHandler {
             let app = self.app.lock().await;
             app.state.get_did()
         };
-        let text = {
-            let app = self.app.lock().await;
-            app.state.get_input().value().to_string()
+        let (draft, parent, attachments) = {
+            let mut app = self.app.lock().await;
+            (
+                app.state.get_draft().map(|draft| Draft {
+                    text: text.clone(),
+                    ..draft
+                }),
+                app.state.get_thread_parent(),
+                app.state.take_attachments(),
+            )
         };
+        let posts = composer::split_thread(&text, numbered);
         {
             let mut app = self.app.lock().await;
             app.state.set_mode(Mode::Normal);
-            app.state.set_input(Input::default());
+            app.state.clear_composer();
+        }
+
+        // The images go with the first post.
+        let mut embed = self
+            .send(draft.clone(), self.attachments_embed(attachments))
+            .await?;
+        let mut reply = parent.as_ref().map(bsky::reply_ref);
+        for (i, text) in posts.iter().enumerate() {
+            let result = self
+                .call(|| {
+                    bsky::send_post(
+                        &agent,
+                        did.clone(),
+                        text.clone(),
+                        reply.clone(),
+                        embed.clone(),
+                    )
+                })
+                .await;
+            let sent = match result {
+                Ok(sent) => sent,
+                Err(e) => {
+                    let e = eyre!(
+                        "Thread stopped at post {}/{}: {}",
+                        i + 1,
+                        posts.len(),
+                        error_message(&e)
+                    );
+                    // Once some posts are out, only the rest is kept, continuing from the last
+                    // one sent.
+                    let draft = match &reply {
+                        Some(reply) if i > 0 => Some(Draft {
+                            kind: DraftKind::ThreadReply {
+                                uri: reply.parent.uri.clone(),
+                            },
+                            text: composer::join_thread(&posts[i..], numbered),
+                            attachments: Vec::new(),
+                            saved_at: Local::now(),
+                        }),
+                        _ => draft,
+                    };
+                    return Err(self.keep_draft(draft, e).await);
+                }
+            };
+            embed = None;
+            let root = reply.map_or_else(|| sent.clone(), |reply| reply.data.root);
+            reply = Some(post::ReplyRefData { root, parent: sent }.into());
         }
-        bsky::send_post(&agent, did, text, None).await?;
+        self.notify(Message::info(format!(
+            "Thread of {} posts sent",
+            posts.len()
+        )))
+        .await;
         self.do_load_timeline(TimelineEvent::Load).await?;
 
         Ok(())
     }
 
+    /// Opens the draft at `index` in the composer, then sends it if `send` is set. The draft is
+    /// kept if opening or sending it fails.
+    async fn do_open_draft(&mut self, index: usize, send: bool) -> Result<()> {
+        let draft = {
+            let mut app = self.app.lock().await;
+            app.take_draft(index)
+                .ok_or_else(|| eyre!("no draft selected"))?
+        };
+        let mode = match self.open_draft(draft.clone(), send).await {
+            Ok(mode) => mode,
+            Err(e) => {
+                let mut app = self.app.lock().await;
+                app.save_draft(draft);
+                return Err(e);
+            }
+        };
+
+        if !send {
+            return Ok(());
+        }
+        // These keep what is in the composer as a draft again if sending fails.
+        let (text, numbered) = {
+            let app = self.app.lock().await;
+            (
+                app.state.get_composer().text().to_string(),
+                app.state.get_number_thread_posts(),
+            )
+        };
+        match mode {
+            Mode::NewThread => self.do_send_thread(text, numbered).await,
+            Mode::Reply => self.do_reply(text).await,
+            Mode::Quote => self.do_quote(text).await,
+            _ => self.do_send_post(text).await,
+        }
+    }
+
+    /// Puts `draft` in the composer, checking first that it can be sent if `send` is set.
+    /// Returns the mode it was opened in.
+    async fn open_draft(&mut self, draft: Draft, send: bool) -> Result<Mode> {
+        let (agent, config) = {
+            let app = self.app.lock().await;
+            (app.state.get_agent().unwrap(), app.state.get_config())
+        };
+        if send {
+            let thread = matches!(
+                draft.kind,
+                DraftKind::Thread | DraftKind::ThreadReply { .. }
+            );
+            if !thread && Composer::new(draft.text.clone()).graphemes() > MAX_GRAPHEMES {
+                bail!("The draft is longer than {MAX_GRAPHEMES} characters, open it to shorten it");
+            }
+            if config.require_alt_text && draft.attachments.iter().any(|a| a.alt.is_empty()) {
+                bail!("Add alt text to every image of the draft before sending it");
+            }
+        }
+
+        let (mode, target) = match &draft.kind {
+            DraftKind::Post => (Mode::Post, None),
+            DraftKind::Thread => (Mode::NewThread, None),
+            DraftKind::ThreadReply { uri } => (
+                Mode::NewThread,
+                Some(self.call(|| bsky::post(&agent, uri.clone())).await?),
+            ),
+            DraftKind::Reply { uri } => (
+                Mode::Reply,
+                Some(self.call(|| bsky::post(&agent, uri.clone())).await?),
+            ),
+            DraftKind::Quote { uri } => (
+                Mode::Quote,
+                Some(self.call(|| bsky::post(&agent, uri.clone())).await?),
+            ),
+        };
+        {
+            let mut app = self.app.lock().await;
+            app.state.set_composer(Composer::new(draft.text));
+            app.state.set_attachments(draft.attachments);
+            app.state.set_target(target);
+            app.state.set_mode(mode.clone());
+        }
+
+        Ok(mode)
+    }
+
     async fn do_load_notifications(&mut self) -> Result<()> {
         {
             let mut app = self.app.lock().await;
@@ -169,119 +566,346 @@ impl IoAsyncHandler {
             let app = self.app.lock().await;
             app.state.get_agent().unwrap()
         };
-        let notifications = bsky::notifications(&agent).await?;
+        let notifications = self.call(|| bsky::notifications(&agent)).await?;
         let mut app = self.app.lock().await;
         app.state
             .set_notifications(Some(notifications.notifications.clone()));
+        app.state.update_hidden_posts();
         app.state.set_loading(false);
 
         Ok(())
     }
 
     async fn do_search(&mut self, event: SearchEvent) -> Result<()> {
-        let current_cursor_index = {
+        let (agent, pages, query) = {
             let app = self.app.lock().await;
-            app.state.get_search_current_cursor_index()
+            (
+                app.state.get_agent().unwrap(),
+                app.state.get_search_pages(),
+                app.state.get_search_query().unwrap_or_default(),
+            )
+        };
+        let (page, query) = match event {
+            SearchEvent::Load(query) => (Page::FIRST, query),
+            SearchEvent::Next => match pages.next() {
+                Some(page) => (page, query),
+                None => return Ok(()),
+            },
+            SearchEvent::Prev => match pages.prev() {
+                Some(page) => (page, query),
+                None => return Ok(()),
+            },
+            SearchEvent::Reload => (pages.current(), query),
         };
-
-        if current_cursor_index == 0 && matches!(event, SearchEvent::Prev) {
-            return Ok(());
-        }
 
         {
             let mut app = self.app.lock().await;
             app.state.set_loading(true);
+            app.state.set_search_query(Some(query.clone()));
         }
 
+        let search_results = self
+            .call(|| bsky::search(&agent, query.clone(), page.cursor.clone()))
+            .await?;
+        let mut app = self.app.lock().await;
+        app.state.set_search_results(Some(
+            search_results
+                .posts
+                .iter()
+                .map(|post| post.data.clone())
+                .collect(),
+        ));
+        app.state.update_hidden_posts();
+        let mut pages = app.state.get_search_pages();
+        pages.set_page(&page, search_results.cursor.clone());
+        app.state.set_search_pages(pages);
+        app.state.set_tab(Tab::Search);
+        app.state.move_search_scroll_top();
+        app.state.set_loading(false);
+
+        Ok(())
+    }
+
+    async fn do_like(&mut self) -> Result<()> {
+        let agent = {
+            let app = self.app.lock().await;
+            app.state.get_agent().unwrap()
+        };
+        let did = {
+            let app = self.app.lock().await;
+            app.state.get_did()
+        };
+        let current_feed = {
+            let app = self.app.lock().await;
+            app.state
+                .get_current_feed()
+                .ok_or_else(|| eyre!("no post selected"))?
+        };
+
+        let liked = current_feed
+            .post
+            .viewer
+            .as_ref()
+            .is_some_and(|viewer| viewer.like.is_some());
+        self.call(|| bsky::toggle_like(&agent, did.clone(), current_feed.clone()))
+            .await?;
+        self.notify(Message::info(if liked { "Unliked" } else { "Liked" }))
+            .await;
+        self.do_load_timeline(TimelineEvent::Reload).await?;
+
+        Ok(())
+    }
+
+    async fn do_repost(&mut self) -> Result<()> {
         let agent = {
             let app = self.app.lock().await;
             app.state.get_agent().unwrap()
         };
+        let did = {
+            let app = self.app.lock().await;
+            app.state.get_did()
+        };
+        let current_feed = {
+            let app = self.app.lock().await;
+            app.state
+                .get_current_feed()
+                .ok_or_else(|| eyre!("no post selected"))?
+        };
+
+        let reposted = current_feed
+            .post
+            .viewer
+            .as_ref()
+            .is_some_and(|viewer| viewer.repost.is_some());
+        self.call(|| bsky::toggle_repost(&agent, did.clone(), current_feed.clone()))
+            .await?;
+        self.notify(Message::info(if reposted {
+            "Unreposted"
+        } else {
+            "Reposted"
+        }))
+        .await;
+        self.do_load_timeline(TimelineEvent::Reload).await?;
+
+        Ok(())
+    }
+
+    async fn do_follow(&mut self, subject: Did, handle: Handle) -> Result<()> {
+        let agent = {
+            let app = self.app.lock().await;
+            app.state.get_agent().unwrap()
+        };
+
+        let uri = self.call(|| bsky::follow(&agent, subject.clone())).await;
+        let mut app = self.app.lock().await;
+        app.state.set_follow_pending(&subject, false);
+        app.state.set_following(&subject, Some(uri?));
+        app.state
+            .push_message(Message::info(format!("Followed @{}", handle.as_str())));
+        Ok(())
+    }
+
+    async fn do_unfollow(&mut self, subject: Did, handle: Handle, uri: String) -> Result<()> {
+        let (agent, did) = {
+            let app = self.app.lock().await;
+            (app.state.get_agent().unwrap(), app.state.get_did())
+        };
+        let rkey = bsky::uri_to_rkey(uri.clone()).ok_or_else(|| eyre!("invalid follow URI"))?;
+
+        let result = self
+            .call(|| bsky::unfollow(&agent, did.clone(), rkey.clone()))
+            .await;
+        let mut app = self.app.lock().await;
+        match result {
+            Ok(()) => {
+                app.state
+                    .push_message(Message::info(format!("Unfollowed @{}", handle.as_str())));
+                Ok(())
+            }
+            Err(e) => {
+                app.state.set_following(&subject, Some(uri));
+                Err(e)
+            }
+        }
+    }
+
+    async fn do_moderate(&mut self, moderation: Moderation) -> Result<()> {
+        let (agent, did) = {
+            let app = self.app.lock().await;
+            (app.state.get_agent().unwrap(), app.state.get_did())
+        };
 
-        let cursor = match &event {
-            SearchEvent::Load(query) => {
+        match moderation {
+            Moderation::Mute(subject, handle) => {
+                self.call(|| bsky::mute(&agent, subject.clone())).await?;
                 let mut app = self.app.lock().await;
-                app.state.set_search_query(Some(query.clone()));
-                None
+                app.state.set_muted(&subject, true);
+                app.state.remove_author(&subject);
+                app.state
+                    .push_message(Message::info(format!("Muted @{}", handle.as_str())));
             }
-            SearchEvent::Next => {
-                let app = self.app.lock().await;
-                app.state.get_search_next_cursor()
+            Moderation::Unmute(subject, handle) => {
+                self.call(|| bsky::unmute(&agent, subject.clone())).await?;
+                let mut app = self.app.lock().await;
+                app.state.set_muted(&subject, false);
+                app.state
+                    .push_message(Message::info(format!("Unmuted @{}", handle.as_str())));
             }
-            SearchEvent::Prev => {
-                let app = self.app.lock().await;
-                app.state.get_search_prev_cursor()
+            Moderation::Block(subject, handle) => {
+                let uri = self.call(|| bsky::block(&agent, subject.clone())).await?;
+                let mut app = self.app.lock().await;
+                app.state.set_blocking(&subject, Some(uri));
+                app.state.remove_author(&subject);
+                app.state
+                    .push_message(Message::info(format!("Blocked @{}", handle.as_str())));
             }
-            SearchEvent::Reload => {
-                let app = self.app.lock().await;
-                app.state.get_search_current_cursor()
+            Moderation::Unblock(subject, handle, uri) => {
+                let rkey = bsky::uri_to_rkey(uri).ok_or_else(|| eyre!("invalid block URI"))?;
+                self.call(|| bsky::unblock(&agent, did.clone(), rkey.clone()))
+                    .await?;
+                let mut app = self.app.lock().await;
+                app.state.set_blocking(&subject, None);
+                app.state
+                    .push_message(Message::info(format!("Unblocked @{}", handle.as_str())));
             }
+        }
+
+        Ok(())
+    }
+
+    async fn do_delete_post(&mut self) -> Result<()> {
+        let (agent, did, post) = {
+            let mut app = self.app.lock().await;
+            let post = app
+                .state
+                .get_post_to_delete()
+                .ok_or_else(|| eyre!("no post selected"))?;
+            app.state.set_post_to_delete(None);
+            (app.state.get_agent().unwrap(), app.state.get_did(), post)
         };
+        if post.author.did != did {
+            return Err(eyre!("only your own posts can be deleted"));
+        }
+        let rkey = bsky::uri_to_rkey(post.uri.clone()).ok_or_else(|| eyre!("invalid post URI"))?;
+
+        self.call(|| bsky::delete_post(&agent, did.clone(), rkey.clone()))
+            .await?;
+        let mut app = self.app.lock().await;
+        app.state.remove_post(&post.uri);
+        app.state.push_message(Message::info("Post deleted"));
 
-        let current_cursor_index = {
+        Ok(())
+    }
+
+    async fn do_reply(&mut self, text: String) -> Result<()> {
+        let agent = {
             let app = self.app.lock().await;
-            app.state.get_search_current_cursor_index()
+            app.state.get_agent().unwrap()
         };
+        let did = {
+            let app = self.app.lock().await;
+            app.state.get_did()
+        };
+        let parent = {
+            let app = self.app.lock().await;
+            app.state
+                .get_target_post()
+                .ok_or_else(|| eyre!("no post selected"))?
+        };
+        let (draft, attachments) = {
+            let mut app = self.app.lock().await;
+            (
+                app.state.get_draft().map(|draft| Draft {
+                    text: text.clone(),
+                    ..draft
+                }),
+                app.state.take_attachments(),
+            )
+        };
+        let reply = bsky::reply_ref(&parent);
 
-        if matches!(event, SearchEvent::Prev) && current_cursor_index == 0 {
-            return Ok(());
+        {
+            let mut app = self.app.lock().await;
+            app.state.set_mode(Mode::Normal);
+            app.state.clear_composer();
         }
 
-        let query_to_use = match &event {
-            SearchEvent::Load(query) => query.clone(),
-            _ => {
-                let app = self.app.lock().await;
-                app.state.get_search_query().unwrap_or_default()
-            }
+        self.send(draft, async {
+            let embed = self.attachments_embed(attachments).await?;
+            self.call(|| {
+                bsky::send_post(
+                    &agent,
+                    did.clone(),
+                    text.clone(),
+                    Some(reply.clone()),
+                    embed.clone(),
+                )
+            })
+            .await
+        })
+        .await?;
+        self.notify(Message::info("Reply sent")).await;
+        self.do_load_timeline(TimelineEvent::Load).await?;
+
+        Ok(())
+    }
+
+    async fn do_quote(&mut self, text: String) -> Result<()> {
+        let agent = {
+            let app = self.app.lock().await;
+            app.state.get_agent().unwrap()
+        };
+        let did = {
+            let app = self.app.lock().await;
+            app.state.get_did()
+        };
+        let (quoted, view_open, tab) = {
+            let app = self.app.lock().await;
+            (
+                app.state
+                    .get_target_post()
+                    .ok_or_else(|| eyre!("no post selected"))?,
+                app.state.is_view_open(),
+                app.state.get_tab(),
+            )
+        };
+        let (draft, attachments) = {
+            let mut app = self.app.lock().await;
+            (
+                app.state.get_draft().map(|draft| Draft {
+                    text: text.clone(),
+                    ..draft
+                }),
+                app.state.take_attachments(),
+            )
         };
 
         {
-            let search_results = bsky::search(&agent, query_to_use, cursor).await?;
             let mut app = self.app.lock().await;
-            app.state.set_search_results(Some(
-                search_results
-                    .posts
-                    .iter()
-                    .map(|post| post.data.clone())
-                    .collect(),
-            ));
-
-            match &event {
-                SearchEvent::Load(_) => {
-                    let mut cursors = app.state.get_search_cursors().clone();
-                    cursors.push(search_results.cursor.clone());
-                    app.state.set_search_cursors(cursors);
-                }
-                SearchEvent::Next => {
-                    let mut cursors = app.state.get_search_cursors().clone();
-                    cursors.push(search_results.cursor.clone());
-                    app.state.set_search_cursors(cursors);
-                    app.state
-                        .set_search_current_cursor_index(current_cursor_index + 1);
-                }
-                SearchEvent::Prev => {
-                    if current_cursor_index == 0 {
-                        return Ok(());
-                    }
-                    app.state
-                        .set_search_current_cursor_index(current_cursor_index - 1);
-                }
-                _ => (),
-            }
-
-            app.state.set_tab(Tab::Search);
-            app.state.move_search_scroll_top();
+            app.state.set_mode(Mode::Normal);
+            app.state.clear_composer();
         }
 
-        {
-            let mut app = self.app.lock().await;
-            app.state.set_loading(false);
+        self.send(draft, async {
+            let media = self.attachments_embed(attachments).await?;
+            let embed = bsky::quote_embed(&quoted, media);
+            self.call(|| {
+                bsky::send_post(&agent, did.clone(), text.clone(), None, Some(embed.clone()))
+            })
+            .await
+        })
+        .await?;
+        self.notify(Message::info("Quote sent")).await;
+        if view_open {
+            self.reload_view().await?;
+        } else if tab == Tab::Home {
+            self.do_load_timeline(TimelineEvent::Load).await?;
         }
 
         Ok(())
     }
 
-    async fn do_like(&mut self) -> Result<()> {
+    async fn do_search_like(&mut self) -> Result<()> {
         let agent = {
             let app = self.app.lock().await;
             app.state.get_agent().unwrap()
@@ -290,18 +914,27 @@ impl IoAsyncHandler {
             let app = self.app.lock().await;
             app.state.get_did()
         };
-        let current_feed = {
+        let current_post = {
             let app = self.app.lock().await;
-            app.state.get_current_feed().unwrap()
+            app.state
+                .get_current_search_result()
+                .ok_or_else(|| eyre!("no post selected"))?
         };
 
-        bsky::toggle_like(&agent, did, current_feed).await?;
-        self.do_load_timeline(TimelineEvent::Reload).await?;
+        let liked = current_post
+            .viewer
+            .as_ref()
+            .is_some_and(|viewer| viewer.like.is_some());
+        self.call(|| bsky::toggle_like_post_view(&agent, did.clone(), current_post.clone()))
+            .await?;
+        self.notify(Message::info(if liked { "Unliked" } else { "Liked" }))
+            .await;
+        self.do_search(SearchEvent::Reload).await?;
 
         Ok(())
     }
 
-    async fn do_repost(&mut self) -> Result<()> {
+    async fn do_search_repost(&mut self) -> Result<()> {
         let agent = {
             let app = self.app.lock().await;
             app.state.get_agent().unwrap()
@@ -310,18 +943,31 @@ impl IoAsyncHandler {
             let app = self.app.lock().await;
             app.state.get_did()
         };
-        let current_feed = {
+        let current_post = {
             let app = self.app.lock().await;
-            app.state.get_current_feed().unwrap()
+            app.state
+                .get_current_search_result()
+                .ok_or_else(|| eyre!("no post selected"))?
         };
 
-        bsky::toggle_repost(&agent, did, current_feed).await?;
-        self.do_load_timeline(TimelineEvent::Reload).await?;
+        let reposted = current_post
+            .viewer
+            .as_ref()
+            .is_some_and(|viewer| viewer.repost.is_some());
+        self.call(|| bsky::toggle_repost_post_view(&agent, did.clone(), current_post.clone()))
+            .await?;
+        self.notify(Message::info(if reposted {
+            "Unreposted"
+        } else {
+            "Reposted"
+        }))
+        .await;
+        self.do_search(SearchEvent::Reload).await?;
 
         Ok(())
     }
 
-    async fn do_reply(&mut self) -> Result<()> {
+    async fn do_search_reply(&mut self, text: String) -> Result<()> {
         let agent = {
             let app = self.app.lock().await;
             app.state.get_agent().unwrap()
@@ -330,40 +976,221 @@ impl IoAsyncHandler {
             let app = self.app.lock().await;
             app.state.get_did()
         };
-        let current_feed = {
+        let current_post = {
             let app = self.app.lock().await;
-            app.state.get_current_feed().unwrap()
+            app.state
+                .get_target_post()
+                .ok_or_else(|| eyre!("no post selected"))?
+        };
+        let (draft, attachments) = {
+            let mut app = self.app.lock().await;
+            (
+                app.state.get_draft().map(|draft| Draft {
+                    text: text.clone(),
+                    ..draft
+                }),
+                app.state.take_attachments(),
+            )
+        };
+        let reply = bsky::reply_ref(&current_post);
+
+        {
+            let mut app = self.app.lock().await;
+            app.state.set_mode(Mode::Normal);
+            app.state.clear_composer();
+        }
+
+        self.send(draft, async {
+            let embed = self.attachments_embed(attachments).await?;
+            self.call(|| {
+                bsky::send_post(
+                    &agent,
+                    did.clone(),
+                    text.clone(),
+                    Some(reply.clone()),
+                    embed.clone(),
+                )
+            })
+            .await
+        })
+        .await?;
+        self.notify(Message::info("Reply sent")).await;
+        self.do_search(SearchEvent::Reload).await?;
+
+        Ok(())
+    }
+
+    async fn do_load_thread(&mut self, event: ThreadEvent) -> Result<()> {
+        let (agent, current) = {
+            let mut app = self.app.lock().await;
+            app.state.set_loading(true);
+            (app.state.get_agent().unwrap(), app.state.get_thread())
+        };
+        let uri = match &event {
+            ThreadEvent::Open(uri) => uri.clone(),
+            ThreadEvent::Reload => match &current {
+                Some(thread) => thread.uri.clone(),
+                None => return Ok(()),
+            },
         };
-        let text = {
+
+        let output = self.call(|| bsky::post_thread(&agent, uri.clone())).await?;
+        let mut app = self.app.lock().await;
+        match (event, current) {
+            (ThreadEvent::Reload, Some(mut thread)) => {
+                thread.reload(output.data.thread);
+                app.state.set_thread(thread);
+            }
+            _ => app.state.push_thread(Thread::new(uri, output.data.thread)),
+        }
+        app.state.set_loading(false);
+
+        Ok(())
+    }
+
+    async fn do_load_profile(&mut self, event: ProfileEvent) -> Result<()> {
+        let (agent, current) = {
             let app = self.app.lock().await;
-            app.state.get_input().value().to_string()
+            (app.state.get_agent().unwrap(), app.state.get_profile())
         };
-        let reply = ReplyRefData {
-            root: strong_ref::MainData {
-                cid: current_feed.post.cid.clone(),
-                uri: current_feed.post.uri.clone(),
+        let (actor, page) = match (&event, &current) {
+            (ProfileEvent::Open(actor), _) => (actor.clone(), Page::FIRST),
+            (ProfileEvent::Next, Some(profile)) => match profile.pages.next() {
+                Some(page) => (profile.actor(), page),
+                None => return Ok(()),
+            },
+            (ProfileEvent::Prev, Some(profile)) => match profile.pages.prev() {
+                Some(page) => (profile.actor(), page),
+                None => return Ok(()),
+            },
+            (ProfileEvent::Reload, Some(profile)) => (profile.actor(), profile.pages.current()),
+            _ => return Ok(()),
+        };
+
+        {
+            let mut app = self.app.lock().await;
+            app.state.set_loading(true);
+        }
+        let feed = self
+            .call(|| bsky::author_feed(&agent, actor.clone(), page.cursor.clone()))
+            .await?;
+        // The counts and relationships only change when the profile is opened or reloaded.
+        let profile = match event {
+            ProfileEvent::Open(_) | ProfileEvent::Reload => {
+                Some(self.call(|| bsky::profile(&agent, actor.clone())).await?)
             }
-            .into(),
-            parent: strong_ref::MainData {
-                cid: current_feed.post.cid.clone(),
-                uri: current_feed.post.uri.clone(),
+            ProfileEvent::Next | ProfileEvent::Prev => None,
+        };
+
+        let mut app = self.app.lock().await;
+        match (event, profile) {
+            (ProfileEvent::Open(_), Some(profile)) => {
+                app.state
+                    .push_profile(Profile::new(profile, feed.data.feed, feed.data.cursor));
             }
-            .into(),
+            (_, profile) => app.state.update_profile(&actor, |current| {
+                if let Some(profile) = profile {
+                    current.profile = profile;
+                }
+                current.set_page(&page, feed.data.feed, feed.data.cursor);
+            }),
+        }
+        app.state.set_loading(false);
+
+        Ok(())
+    }
+
+    async fn do_load_follows(&mut self, event: FollowsEvent) -> Result<()> {
+        let (agent, current) = {
+            let app = self.app.lock().await;
+            (app.state.get_agent().unwrap(), app.state.get_follow_list())
+        };
+        let (kind, actor, page) = match (&event, &current) {
+            (FollowsEvent::Open(kind, actor), _) => (*kind, actor.clone(), Page::FIRST),
+            (FollowsEvent::Next, Some(list)) => match list.pages.next() {
+                Some(page) => (list.kind, list.actor(), page),
+                None => return Ok(()),
+            },
+            (FollowsEvent::Prev, Some(list)) => match list.pages.prev() {
+                Some(page) => (list.kind, list.actor(), page),
+                None => return Ok(()),
+            },
+            (FollowsEvent::Reload, Some(list)) => (list.kind, list.actor(), list.pages.current()),
+            _ => return Ok(()),
         };
 
         {
             let mut app = self.app.lock().await;
-            app.state.set_mode(Mode::Normal);
-            app.state.set_input(Input::default());
+            app.state.set_loading(true);
         }
+        let (subject, accounts, next_cursor) = match kind {
+            FollowListKind::Followers => {
+                let output = self
+                    .call(|| bsky::followers(&agent, actor.clone(), page.cursor.clone()))
+                    .await?;
+                (
+                    Some(output.data.subject.data),
+                    output.data.followers,
+                    output.data.cursor,
+                )
+            }
+            FollowListKind::Follows => {
+                let output = self
+                    .call(|| bsky::follows(&agent, actor.clone(), page.cursor.clone()))
+                    .await?;
+                (
+                    Some(output.data.subject.data),
+                    output.data.follows,
+                    output.data.cursor,
+                )
+            }
+            FollowListKind::Mutes => {
+                let output = self
+                    .call(|| bsky::mutes(&agent, page.cursor.clone()))
+                    .await?;
+                (None, output.data.mutes, output.data.cursor)
+            }
+            FollowListKind::Blocks => {
+                let output = self
+                    .call(|| bsky::blocks(&agent, page.cursor.clone()))
+                    .await?;
+                (None, output.data.blocks, output.data.cursor)
+            }
+        };
+        let accounts = accounts.into_iter().map(|account| account.data).collect();
 
-        bsky::send_post(&agent, did, text, Some(reply.into())).await?;
-        self.do_load_timeline(TimelineEvent::Load).await?;
+        let mut app = self.app.lock().await;
+        match event {
+            FollowsEvent::Open(..) => {
+                app.state
+                    .push_follow_list(FollowList::new(kind, subject, accounts, next_cursor))
+            }
+            _ => app.state.update_follow_list(kind, &actor, |list| {
+                list.subject = subject;
+                list.set_page(&page, accounts, next_cursor);
+            }),
+        }
+        app.state.set_loading(false);
 
         Ok(())
     }
 
-    async fn do_search_like(&mut self) -> Result<()> {
+    /// Reloads the open thread, profile or follow list, to show what an action changed.
+    async fn reload_view(&mut self) -> Result<()> {
+        let (is_profile_view, is_follow_list_view) = {
+            let app = self.app.lock().await;
+            (app.state.is_profile_view(), app.state.is_follow_list_view())
+        };
+        if is_profile_view {
+            self.do_load_profile(ProfileEvent::Reload).await
+        } else if is_follow_list_view {
+            self.do_load_follows(FollowsEvent::Reload).await
+        } else {
+            self.do_load_thread(ThreadEvent::Reload).await
+        }
+    }
+
+    async fn do_view_like(&mut self) -> Result<()> {
         let agent = {
             let app = self.app.lock().await;
             app.state.get_agent().unwrap()
@@ -374,16 +1201,25 @@ impl IoAsyncHandler {
         };
         let current_post = {
             let app = self.app.lock().await;
-            app.state.get_current_search_result().unwrap()
+            app.state
+                .get_selected_post()
+                .ok_or_else(|| eyre!("no post selected"))?
         };
 
-        bsky::toggle_like_post_view(&agent, did, current_post).await?;
-        self.do_search(SearchEvent::Reload).await?;
+        let liked = current_post
+            .viewer
+            .as_ref()
+            .is_some_and(|viewer| viewer.like.is_some());
+        self.call(|| bsky::toggle_like_post_view(&agent, did.clone(), current_post.clone()))
+            .await?;
+        self.notify(Message::info(if liked { "Unliked" } else { "Liked" }))
+            .await;
+        self.reload_view().await?;
 
         Ok(())
     }
 
-    async fn do_search_repost(&mut self) -> Result<()> {
+    async fn do_view_repost(&mut self) -> Result<()> {
         let agent = {
             let app = self.app.lock().await;
             app.state.get_agent().unwrap()
@@ -394,16 +1230,29 @@ impl IoAsyncHandler {
         };
         let current_post = {
             let app = self.app.lock().await;
-            app.state.get_current_search_result().unwrap()
+            app.state
+                .get_selected_post()
+                .ok_or_else(|| eyre!("no post selected"))?
         };
 
-        bsky::toggle_repost_post_view(&agent, did, current_post).await?;
-        self.do_search(SearchEvent::Reload).await?;
+        let reposted = current_post
+            .viewer
+            .as_ref()
+            .is_some_and(|viewer| viewer.repost.is_some());
+        self.call(|| bsky::toggle_repost_post_view(&agent, did.clone(), current_post.clone()))
+            .await?;
+        self.notify(Message::info(if reposted {
+            "Unreposted"
+        } else {
+            "Reposted"
+        }))
+        .await;
+        self.reload_view().await?;
 
         Ok(())
     }
 
-    async fn do_search_reply(&mut self) -> Result<()> {
+    async fn do_view_reply(&mut self, text: String) -> Result<()> {
         let agent = {
             let app = self.app.lock().await;
             app.state.get_agent().unwrap()
@@ -414,34 +1263,103 @@ impl IoAsyncHandler {
         };
         let current_post = {
             let app = self.app.lock().await;
-            app.state.get_current_search_result().unwrap()
+            app.state
+                .get_target_post()
+                .ok_or_else(|| eyre!("no post selected"))?
         };
-        let text = {
-            let app = self.app.lock().await;
-            app.state.get_input().value().to_string()
-        };
-        let reply = ReplyRefData {
-            root: strong_ref::MainData {
-                cid: current_post.cid.clone(),
-                uri: current_post.uri.clone(),
-            }
-            .into(),
-            parent: strong_ref::MainData {
-                cid: current_post.cid.clone(),
-                uri: current_post.uri.clone(),
-            }
-            .into(),
+        let (draft, attachments) = {
+            let mut app = self.app.lock().await;
+            (
+                app.state.get_draft().map(|draft| Draft {
+                    text: text.clone(),
+                    ..draft
+                }),
+                app.state.take_attachments(),
+            )
         };
+        let reply = bsky::reply_ref(&current_post);
 
         {
             let mut app = self.app.lock().await;
             app.state.set_mode(Mode::Normal);
-            app.state.set_input(Input::default());
+            app.state.clear_composer();
         }
 
-        bsky::send_post(&agent, did, text, Some(reply.into())).await?;
-        self.do_search(SearchEvent::Reload).await?;
+        self.send(draft, async {
+            let embed = self.attachments_embed(attachments).await?;
+            self.call(|| {
+                bsky::send_post(
+                    &agent,
+                    did.clone(),
+                    text.clone(),
+                    Some(reply.clone()),
+                    embed.clone(),
+                )
+            })
+            .await
+        })
+        .await?;
+        self.notify(Message::info("Reply sent")).await;
+        self.reload_view().await?;
 
         Ok(())
     }
+
+    /// Reads and uploads the images attached to the post being sent.
+    async fn attachments_embed(
+        &self,
+        attachments: Vec<Attachment>,
+    ) -> Result<Option<Union<post::RecordEmbedRefs>>> {
+        if attachments.is_empty() {
+            return Ok(None);
+        }
+        let downscale = self.config.downscale_images();
+        let mut images = Vec::new();
+        for attachment in attachments {
+            let path = attachment.path.clone();
+            let image =
+                tokio::task::spawn_blocking(move || attachment::load(&path, downscale)).await??;
+            images.push((image, attachment.alt));
+        }
+
+        let agent = {
+            let app = self.app.lock().await;
+            app.state
+                .get_agent()
+                .ok_or_else(|| eyre!("not logged in"))?
+        };
+        let embed = self
+            .call(|| bsky::images_embed(&agent, images.clone()))
+            .await?;
+        Ok(Some(embed))
+    }
+
+    /// Downloads a thumbnail in the background so that other events are not held up by it.
+    /// Failures are only shown in the preview pane.
+    async fn do_load_thumbnail(&mut self, url: String) -> Result<()> {
+        let app = Arc::clone(&self.app);
+        let http = self.http.clone();
+        tokio::spawn(async move {
+            let thumbnail = match preview::fetch(&http, &url).await {
+                Ok(image) => Thumbnail::Loaded(image),
+                Err(_) => Thumbnail::Failed,
+            };
+            app.lock().await.set_thumbnail(url, thumbnail);
+        });
+
+        Ok(())
+    }
+}
+
+/// Turns an API error into a short message for the status bar.
+fn error_message(e: &eyre::Report) -> String {
+    if bsky::is_rate_limited(e) {
+        "Rate limited, try again later".to_string()
+    } else if bsky::is_network_error(e) {
+        format!("Network error: {e:#}")
+    } else if bsky::is_auth_error(e) {
+        format!("Session expired and logging in again failed: {e:#}")
+    } else {
+        format!("{e:#}")
+    }
 }
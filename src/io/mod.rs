@@ -1,18 +1,66 @@
 pub mod handler;
 
+use atrium_api::{
+    app::bsky::actor::defs::MutedWord,
+    types::string::{Did, Handle},
+};
+
+use crate::app::{follows::FollowListKind, state::Moderation};
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum IoEvent {
     Initialize,
+    Login(LoginEvent),
+    /// Switches to the account at the given index, logging in if it has not been yet.
+    SwitchAccount(usize),
     LoadTimeline(TimelineEvent),
     LoadNotifications,
-    SendPost,
+    /// Sends a post with the given text, as written when it was sent.
+    SendPost(String),
+    /// Sends the given text as a chain of posts replying to each other, ending each post with its
+    /// position in the thread if the flag is set.
+    SendThread(String, bool),
     Like,
     Repost,
-    Reply,
+    Reply(String),
     Search(SearchEvent),
     SearchLike,
     SearchRepost,
-    SearchReply,
+    SearchReply(String),
+    LoadThread(ThreadEvent),
+    LoadProfile(ProfileEvent),
+    LoadFollows(FollowsEvent),
+    /// Likes the selected post in the open thread or profile.
+    ViewLike,
+    ViewRepost,
+    ViewReply(String),
+    /// Sends a post with the given text quoting the selected post in the timeline, search
+    /// results or thread.
+    Quote(String),
+    /// Downloads the thumbnail with the given URL for the image preview.
+    LoadThumbnail(String),
+    /// Follows the account, whose handle is used in messages.
+    Follow(Did, Handle),
+    /// Unfollows the account by deleting the follow record with the given URI.
+    Unfollow(Did, Handle, String),
+    /// Mutes, unmutes, blocks or unblocks an account.
+    Moderate(Moderation),
+    /// Deletes the post the delete confirmation was shown for.
+    DeletePost,
+    /// Replaces our muted words and hides the loaded posts that match them.
+    SaveMutedWords(Vec<MutedWord>),
+    /// Opens the draft at the given index in the composer.
+    OpenDraft(usize),
+    /// Sends the draft at the given index without opening it.
+    SendDraft(usize),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LoginEvent {
+    pub account_index: usize,
+    pub identifier: String,
+    pub password: String,
+    pub auth_factor_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -30,3 +78,29 @@ pub enum SearchEvent {
     Prev,
     Reload,
 }
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ThreadEvent {
+    /// Opens the thread of the post with the given URI on top of the current view.
+    Open(String),
+    Reload,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ProfileEvent {
+    /// Opens the profile of the given handle or DID on top of the current view.
+    Open(String),
+    Next,
+    Prev,
+    Reload,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FollowsEvent {
+    /// Opens the followers or follows of the given handle or DID on top of the current view. The
+    /// handle is left empty for our mutes and blocks.
+    Open(FollowListKind, String),
+    Next,
+    Prev,
+    Reload,
+}
@@ -10,7 +10,12 @@ use eyre::Result;
 use ratatui::{backend::CrosstermBackend, Terminal};
 
 use crate::{
-    app::{ui, App, AppReturn},
+    app::{
+        editor,
+        preview::Thumbnail,
+        ui::{self, graphics::Graphics},
+        App, AppReturn,
+    },
     inputs::{events::Events, InputEvent},
     io::IoEvent,
 };
@@ -20,9 +25,12 @@ pub async fn start_ui(
     skip_splash: bool,
     splash: String,
 ) -> Result<()> {
-    let stdout = stdout();
     crossterm::terminal::enable_raw_mode()?;
-    let backend = CrosstermBackend::new(stdout);
+    // Lets the composer tell Shift+Enter and Ctrl+Enter apart from Enter.
+    let keyboard_enhancement =
+        crossterm::terminal::supports_keyboard_enhancement().unwrap_or(false);
+    enter_tui(keyboard_enhancement)?;
+    let backend = CrosstermBackend::new(stdout());
     let mut terminal = Terminal::new(backend)?;
     terminal.clear()?;
     terminal.hide_cursor()?;
@@ -30,6 +38,7 @@ pub async fn start_ui(
 
     let tick_rate = Duration::from_millis(200);
     let mut events = Events::new(tick_rate);
+    let mut graphics = Graphics::default();
 
     {
         let mut app = app.lock().await;
@@ -47,10 +56,13 @@ pub async fn start_ui(
             })?;
 
             loop {
-                let app = app.lock().await;
-                if app.state.get_timeline().is_some() {
-                    break;
+                {
+                    let app = app.lock().await;
+                    if app.state.get_timeline().is_some() || app.state.is_login() {
+                        break;
+                    }
                 }
+                tokio::time::sleep(Duration::from_millis(10)).await;
             }
 
             tokio::time::sleep(Duration::from_millis(50)).await;
@@ -61,22 +73,87 @@ pub async fn start_ui(
     loop {
         let mut app = app.lock().await;
 
-        terminal.draw(|rect| ui::render::<CrosstermBackend<std::io::Stdout>>(rect, &app))?;
+        let mut next_graphics = None;
+        terminal.draw(|rect| {
+            next_graphics = ui::render::<CrosstermBackend<std::io::Stdout>>(rect, &app)
+        })?;
+        if graphics.needs_clear(next_graphics.as_ref()) {
+            terminal.clear()?;
+            terminal.draw(|rect| {
+                ui::render::<CrosstermBackend<std::io::Stdout>>(rect, &app);
+            })?;
+        }
+        let image = next_graphics
+            .as_ref()
+            .and_then(|next| match app.get_thumbnail(&next.url) {
+                Some(Thumbnail::Loaded(image)) => Some(image),
+                _ => None,
+            });
+        graphics.show(terminal.backend_mut(), next_graphics, image)?;
 
         let result = match events.next().await {
             InputEvent::Input(key) => app.do_action(key).await,
+            InputEvent::Paste(text) => app.do_paste(text).await,
             InputEvent::Tick => app.update_on_tick().await,
         };
 
-        if result == AppReturn::Exit {
-            events.close();
-            break;
+        match result {
+            AppReturn::Exit => {
+                app.save_unsent_draft();
+                events.close();
+                break;
+            }
+            AppReturn::OpenEditor => {
+                let template = app.editor_template();
+                graphics.show(terminal.backend_mut(), None, None)?;
+                terminal.clear()?;
+                terminal.show_cursor()?;
+                events.pause().await;
+                leave_tui(keyboard_enhancement)?;
+
+                let edited = editor::edit(&template).await;
+
+                enter_tui(keyboard_enhancement)?;
+                events.resume();
+                terminal.hide_cursor()?;
+                // Draws everything again over what the editor left on the screen.
+                terminal.clear()?;
+                app.set_edited_text(edited);
+            }
+            AppReturn::Continue => {}
         }
     }
 
+    graphics.show(terminal.backend_mut(), None, None)?;
     terminal.clear()?;
     terminal.show_cursor()?;
-    crossterm::terminal::disable_raw_mode()?;
+    leave_tui(keyboard_enhancement)?;
+
+    Ok(())
+}
 
+/// Sets the terminal up for the TUI: raw mode, bracketed paste and, if the terminal supports
+/// it, the kitty keyboard protocol.
+fn enter_tui(keyboard_enhancement: bool) -> Result<()> {
+    crossterm::terminal::enable_raw_mode()?;
+    crossterm::execute!(stdout(), crossterm::event::EnableBracketedPaste)?;
+    if keyboard_enhancement {
+        crossterm::execute!(
+            stdout(),
+            crossterm::event::PushKeyboardEnhancementFlags(
+                crossterm::event::KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+            )
+        )?;
+    }
+    Ok(())
+}
+
+/// Undoes [`enter_tui`], for when the app exits or hands the terminal over to another program.
+fn leave_tui(keyboard_enhancement: bool) -> Result<()> {
+    if keyboard_enhancement {
+        crossterm::execute!(stdout(), crossterm::event::PopKeyboardEnhancementFlags)?;
+    }
+    crossterm::execute!(stdout(), crossterm::event::DisableBracketedPaste)?;
+    crossterm::terminal::disable_raw_mode()?;
     Ok(())
 }
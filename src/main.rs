@@ -1,9 +1,14 @@
 use std::sync::Arc;
 
-use seahorse::{App as SeahorseApp, Command, Context};
+use seahorse::{App as SeahorseApp, Command, Context, Flag, FlagType};
 
 use bsky_tui::{
-    app::{config::AppConfig, App},
+    app::{
+        config::AppConfig,
+        credentials::{Credentials, PasswordStore},
+        App,
+    },
+    bsky,
     io::{handler::IoAsyncHandler, IoEvent},
     start_ui,
     utils::get_splash,
@@ -16,12 +21,18 @@ fn main() {
         .version(env!("CARGO_PKG_VERSION"))
         .usage(format!("{} [args]", env!("CARGO_PKG_NAME")))
         .action(action)
+        .flag(
+            Flag::new("service", FlagType::String)
+                .description("PDS URL to log in to (overrides `service` in the config file)")
+                .alias("s"),
+        )
         .command(config_command())
+        .command(login_command())
         .run(std::env::args().collect());
 }
 
 #[tokio::main]
-async fn action(_c: &Context) {
+async fn action(c: &Context) {
     if !AppConfig::config_exists() {
         let path = AppConfig::config_path();
         println!("Config file not found: {}", path.to_str().unwrap());
@@ -29,9 +40,14 @@ async fn action(_c: &Context) {
         return;
     }
 
-    let config = AppConfig::load().unwrap();
-    if let Err(e) = config.check_required_fields() {
-        println!("Config file error: {}", e);
+    warn_if_world_readable();
+
+    let mut config = AppConfig::load().unwrap();
+    if let Ok(service) = c.string_flag("service") {
+        config.service = Some(service);
+    }
+    if let Err(e) = config.load_passwords() {
+        println!("Failed to load passwords: {}", e);
         return;
     }
 
@@ -40,8 +56,9 @@ async fn action(_c: &Context) {
     let app = Arc::new(tokio::sync::Mutex::new(App::new(sync_io_tx.clone())));
     let app_ui = Arc::clone(&app);
 
+    let handler_config = config.clone();
     tokio::spawn(async move {
-        let mut handler = IoAsyncHandler::new(app);
+        let mut handler = IoAsyncHandler::new(app, handler_config);
         while let Some(io_event) = sync_io_rx.recv().await {
             handler.handle_io_event(io_event).await;
         }
@@ -50,6 +67,15 @@ async fn action(_c: &Context) {
     start_ui(&app_ui, config.skip_splash, get_splash(config.splash_path))
         .await
         .unwrap();
+
+    // Persist the latest tokens so the next launch can resume without logging in.
+    let app = app_ui.lock().await;
+    for state in app.states() {
+        if let Some(agent) = state.get_agent() {
+            let path = AppConfig::session_path(&state.get_account());
+            let _ = bsky::save_session(&agent, &path).await;
+        }
+    }
 }
 
 fn config_command() -> Command {
@@ -71,3 +97,68 @@ fn config_command() -> Command {
             );
         })
 }
+
+fn login_command() -> Command {
+    Command::new("login")
+        .description("Store an account password in the keyring or encrypted credentials file")
+        .usage(format!("{} login [identifier]", env!("CARGO_PKG_NAME")))
+        .action(|c| {
+            if !AppConfig::config_exists() {
+                println!(
+                    "Config file not found: {}",
+                    AppConfig::config_path().to_str().unwrap()
+                );
+                return;
+            }
+            warn_if_world_readable();
+
+            let config = AppConfig::load().unwrap();
+            let identifier = match c.args.first() {
+                Some(identifier) => identifier.clone(),
+                None => match config.accounts().first() {
+                    Some(account) => account.identifier.clone(),
+                    None => {
+                        println!("Specify an identifier: bsky_tui login <identifier>");
+                        return;
+                    }
+                },
+            };
+            let store = match config.password_store {
+                PasswordStore::Config => PasswordStore::Keyring,
+                store => store,
+            };
+
+            let password = match rpassword::prompt_password(format!("Password for {identifier}: "))
+            {
+                Ok(password) if !password.is_empty() => password,
+                _ => {
+                    println!("No password entered");
+                    return;
+                }
+            };
+            match Credentials::new(store).set(&identifier, &password) {
+                Ok(location) => println!("Password for {} stored in {}", identifier, location),
+                Err(e) => {
+                    println!("Failed to store password: {}", e);
+                    return;
+                }
+            }
+            if config.password_store == PasswordStore::Config {
+                println!(
+                    "Set `password_store = \"keyring\"` and remove `password` in {} to use it",
+                    AppConfig::config_path().to_str().unwrap()
+                );
+            }
+        })
+}
+
+fn warn_if_world_readable() {
+    if AppConfig::is_world_readable() {
+        let path = AppConfig::config_path();
+        println!(
+            "Warning: {} is readable by other users. Run `chmod 600 {}` to restrict it.",
+            path.to_str().unwrap(),
+            path.to_str().unwrap()
+        );
+    }
+}